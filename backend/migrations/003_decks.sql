@@ -0,0 +1,23 @@
+-- Saved decks owned by users
+CREATE TABLE IF NOT EXISTS decks (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Index for listing a user's decks
+CREATE INDEX IF NOT EXISTS idx_decks_user_id ON decks(user_id);
+
+-- Card references making up each deck, in list order
+CREATE TABLE IF NOT EXISTS deck_cards (
+    deck_id UUID NOT NULL REFERENCES decks(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    set_code VARCHAR(10),
+    collector_number VARCHAR(20),
+    quantity INTEGER NOT NULL DEFAULT 1,
+    is_commander BOOLEAN NOT NULL DEFAULT false,
+    PRIMARY KEY (deck_id, position)
+);
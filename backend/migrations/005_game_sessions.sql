@@ -0,0 +1,6 @@
+-- Snapshots of running games so they survive a server restart
+CREATE TABLE IF NOT EXISTS game_sessions (
+    id VARCHAR(32) PRIMARY KEY,
+    state TEXT NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
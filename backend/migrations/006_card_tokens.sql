@@ -0,0 +1,13 @@
+-- Scryfall ids and token flags, used to link cards to the tokens they create
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS scryfall_id VARCHAR(64);
+ALTER TABLE cards ADD COLUMN IF NOT EXISTS is_token BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_cards_scryfall_id ON cards(scryfall_id);
+
+-- Tokens each card creates, from Scryfall's all_parts
+CREATE TABLE IF NOT EXISTS card_tokens (
+    card_scryfall_id VARCHAR(64) NOT NULL,
+    token_scryfall_id VARCHAR(64) NOT NULL,
+    token_name VARCHAR(255) NOT NULL,
+    PRIMARY KEY (card_scryfall_id, token_scryfall_id)
+);
@@ -0,0 +1,6 @@
+-- Games expired after sitting idle, kept for reference
+CREATE TABLE IF NOT EXISTS game_archive (
+    id VARCHAR(32) PRIMARY KEY,
+    state TEXT NOT NULL,
+    archived_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::game::Card;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Section {
+    Main,
+    Commander,
+    Ignored,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecklistEntry {
+    pub line_number: usize,
+    pub line: String,
+    pub quantity: usize,
+    pub name: String,
+    pub set_code: Option<String>,
+    pub collector_number: Option<String>,
+    pub is_commander: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecklistError {
+    pub line_number: usize,
+    pub line: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ResolvedDeck {
+    pub library: Vec<Card>,
+    pub command_zone: Vec<Card>,
+    pub errors: Vec<DecklistError>,
+}
+
+/// Parses a text decklist such as `1 Sol Ring (C21) 263`.
+///
+/// Lines under a `Commander` header go to the command zone, lines under
+/// `Sideboard`/`Maybeboard` are skipped, everything else is the library.
+pub fn parse_decklist(text: &str) -> (Vec<DecklistEntry>, Vec<DecklistError>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut section = Section::Main;
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(new_section) = parse_section_header(line) {
+            section = new_section;
+            continue;
+        }
+
+        // Plain comments (anything starting with // or # that isn't a header)
+        if line.starts_with("//") || line.starts_with('#') {
+            continue;
+        }
+
+        if section == Section::Ignored {
+            continue;
+        }
+
+        match parse_line(line) {
+            Some((quantity, name, set_code, collector_number)) => {
+                entries.push(DecklistEntry {
+                    line_number,
+                    line: line.to_string(),
+                    quantity,
+                    name,
+                    set_code,
+                    collector_number,
+                    is_commander: section == Section::Commander,
+                });
+            }
+            None => {
+                errors.push(DecklistError {
+                    line_number,
+                    line: line.to_string(),
+                    message: "Could not parse line".to_string(),
+                });
+            }
+        }
+    }
+
+    (entries, errors)
+}
+
+fn parse_section_header(line: &str) -> Option<Section> {
+    let header = line
+        .trim_start_matches("//")
+        .trim()
+        .trim_end_matches(':')
+        .trim()
+        .to_lowercase();
+
+    match header.as_str() {
+        "commander" | "commanders" => Some(Section::Commander),
+        "deck" | "main" | "mainboard" | "library" => Some(Section::Main),
+        "sideboard" | "maybeboard" | "considering" => Some(Section::Ignored),
+        _ => None,
+    }
+}
+
+/// Splits `<qty> <name> (<SET>) <number>` into its parts. Quantity, set and
+/// collector number are all optional.
+fn parse_line(line: &str) -> Option<(usize, String, Option<String>, Option<String>)> {
+    // Drop trailing foil/etched markers some exporters append (e.g. "*F*")
+    let mut rest = line.trim();
+    while let Some(stripped) = rest.strip_suffix('*') {
+        match stripped.rfind('*') {
+            Some(start) => rest = stripped[..start].trim_end(),
+            None => break,
+        }
+    }
+
+    let (quantity, rest) = match rest.split_once(char::is_whitespace) {
+        Some((first, remainder)) => {
+            match first.trim_end_matches(['x', 'X']).parse::<usize>() {
+                Ok(qty) => (qty, remainder.trim()),
+                Err(_) => (1, rest),
+            }
+        }
+        None => (1, rest),
+    };
+
+    if quantity == 0 {
+        return None;
+    }
+
+    let (name, set_code, collector_number) = match (rest.rfind('('), rest.rfind(')')) {
+        (Some(open), Some(close)) if open < close => {
+            let name = rest[..open].trim();
+            let set_code = rest[open + 1..close].trim();
+            let collector_number = rest[close + 1..].trim();
+            (
+                name.to_string(),
+                (!set_code.is_empty()).then(|| set_code.to_lowercase()),
+                (!collector_number.is_empty()).then(|| collector_number.to_string()),
+            )
+        }
+        _ => (rest.to_string(), None, None),
+    };
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((quantity, name, set_code, collector_number))
+}
+
+/// Looks up a single entry in the cards table, returning
+/// `(name, set_code, collector_number, is_two_sided)` for the matched printing.
+async fn resolve_entry(
+    pool: &PgPool,
+    entry: &DecklistEntry,
+) -> Result<Option<(String, String, String, bool)>, sqlx::Error> {
+    if let (Some(set_code), Some(collector_number)) = (&entry.set_code, &entry.collector_number) {
+        let found = sqlx::query_as::<_, (String, String, String, bool)>(
+            "SELECT name, set_code, collector_number, is_two_sided FROM cards WHERE set_code = $1 AND collector_number = $2 LIMIT 1"
+        )
+        .bind(set_code)
+        .bind(collector_number)
+        .fetch_optional(pool)
+        .await?;
+
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+
+    // Fall back to a name match, preferring the requested set when given.
+    // Double-faced cards are stored as "Front // Back", so match the front face too.
+    // Tokens share names with real cards (Treasure, Food), so those come last.
+    sqlx::query_as::<_, (String, String, String, bool)>(
+        "SELECT name, set_code, collector_number, is_two_sided FROM cards
+         WHERE (LOWER(name) = LOWER($1) OR LOWER(name) LIKE LOWER($1) || ' // %')
+         ORDER BY is_token, (set_code = COALESCE($2, '')) DESC, created_at DESC
+         LIMIT 1"
+    )
+    .bind(&entry.name)
+    .bind(&entry.set_code)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Resolves parsed entries against the cards table and builds real cards.
+pub async fn resolve_decklist(pool: &PgPool, entries: &[DecklistEntry]) -> ResolvedDeck {
+    let mut deck = ResolvedDeck::default();
+
+    for entry in entries {
+        let (name, set_code, collector_number, is_two_sided) = match resolve_entry(pool, entry).await {
+            Ok(Some(found)) => found,
+            Ok(None) => {
+                deck.errors.push(DecklistError {
+                    line_number: entry.line_number,
+                    line: entry.line.clone(),
+                    message: format!("Card not found: {}", entry.name),
+                });
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("Database error resolving decklist line {}: {}", entry.line_number, e);
+                deck.errors.push(DecklistError {
+                    line_number: entry.line_number,
+                    line: entry.line.clone(),
+                    message: format!("Database error: {}", e),
+                });
+                continue;
+            }
+        };
+
+        for _ in 0..entry.quantity {
+            let card = Card {
+                id: Uuid::new_v4().to_string(),
+                name: name.clone(),
+                is_tapped: false,
+                is_flipped: false,
+                is_commander: entry.is_commander,
+                is_token: false,
+                is_two_sided,
+                is_back_face: false,
+                set_code: Some(set_code.clone()),
+                collector_number: Some(collector_number.clone()),
+                position_x: 0.0,
+                position_y: 0.0,
+                is_revealed: false,
+                counters: Default::default(),
+                attached_to: None,
+                is_aura: false,
+                owner_id: None,
+            };
+            if entry.is_commander {
+                deck.command_zone.push(card);
+            } else {
+                deck.library.push(card);
+            }
+        }
+    }
+
+    deck
+}
+
+/// Parses and resolves a decklist in one go.
+pub async fn load_decklist(pool: &PgPool, text: &str) -> ResolvedDeck {
+    let (entries, parse_errors) = parse_decklist(text);
+    let mut deck = resolve_decklist(pool, &entries).await;
+    deck.errors.splice(0..0, parse_errors);
+    deck.errors.sort_by_key(|e| e.line_number);
+    deck
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(line: &str) -> (usize, String, Option<String>, Option<String>) {
+        parse_line(line).unwrap()
+    }
+
+    #[test]
+    fn quantities_are_optional_and_may_end_in_x() {
+        assert_eq!(parsed("4 Island").0, 4);
+        assert_eq!(parsed("4x Island").0, 4);
+        assert_eq!(parsed("10X Forest").0, 10);
+        assert_eq!(parsed("Sol Ring"), (1, "Sol Ring".to_string(), None, None));
+    }
+
+    #[test]
+    fn set_and_collector_number_are_split_off_the_name() {
+        assert_eq!(
+            parsed("1 Sol Ring (C21) 263"),
+            (1, "Sol Ring".to_string(), Some("c21".to_string()), Some("263".to_string()))
+        );
+        assert_eq!(
+            parsed("1 Sol Ring (C21)"),
+            (1, "Sol Ring".to_string(), Some("c21".to_string()), None)
+        );
+        assert_eq!(
+            parsed("1 Sol Ring (C21) 263 *F*"),
+            (1, "Sol Ring".to_string(), Some("c21".to_string()), Some("263".to_string()))
+        );
+    }
+
+    #[test]
+    fn section_headers_choose_where_cards_go() {
+        let text = "Commander\n1 Atraxa, Praetors' Voice\n\nDeck\n1 Sol Ring\n// Sideboard\n1 Swords to Plowshares\nMainboard:\n1 Island";
+        let (entries, errors) = parse_decklist(text);
+        assert!(errors.is_empty());
+        let cards: Vec<_> = entries.iter().map(|e| (e.name.as_str(), e.is_commander)).collect();
+        assert_eq!(
+            cards,
+            [("Atraxa, Praetors' Voice", true), ("Sol Ring", false), ("Island", false)]
+        );
+    }
+
+    #[test]
+    fn blank_and_comment_lines_are_skipped() {
+        let text = "\n   \n// my favourite deck\n# built in 2024\n2 Island\n";
+        let (entries, errors) = parse_decklist(text);
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].line_number, 5);
+        assert_eq!(entries[0].quantity, 2);
+    }
+
+    #[test]
+    fn malformed_lines_are_reported_with_their_line_number() {
+        let text = "1 Sol Ring\n0 Island\n2 (C21) 263\n1 Forest";
+        let (entries, errors) = parse_decklist(text);
+        assert_eq!(entries.len(), 2);
+        let bad: Vec<_> = errors.iter().map(|e| (e.line_number, e.line.as_str())).collect();
+        assert_eq!(bad, [(2, "0 Island"), (3, "2 (C21) 263")]);
+    }
+}
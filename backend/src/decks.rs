@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgPool, Row};
+use uuid::Uuid;
+
+use crate::decklist::DecklistEntry;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckCard {
+    pub name: String,
+    pub set_code: Option<String>,
+    pub collector_number: Option<String>,
+    pub quantity: i32,
+    #[serde(default)]
+    pub is_commander: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Deck {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub cards: Vec<DeckCard>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckSummary {
+    pub id: String,
+    pub name: String,
+    pub card_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveDeckRequest {
+    pub name: String,
+    pub cards: Vec<DeckCard>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeckResponse {
+    pub success: bool,
+    pub message: String,
+    pub deck: Option<Deck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeckListResponse {
+    pub success: bool,
+    pub message: String,
+    pub decks: Vec<DeckSummary>,
+}
+
+impl DeckCard {
+    /// Converts a saved card reference into a decklist entry so it can be
+    /// resolved the same way as an imported text decklist.
+    pub fn to_entry(&self, line_number: usize) -> DecklistEntry {
+        let line = match (&self.set_code, &self.collector_number) {
+            (Some(set), Some(number)) => format!("{} {} ({}) {}", self.quantity, self.name, set.to_uppercase(), number),
+            _ => format!("{} {}", self.quantity, self.name),
+        };
+        DecklistEntry {
+            line_number,
+            line,
+            quantity: self.quantity.max(0) as usize,
+            name: self.name.clone(),
+            set_code: self.set_code.as_ref().map(|s| s.to_lowercase()),
+            collector_number: self.collector_number.clone(),
+            is_commander: self.is_commander,
+        }
+    }
+}
+
+fn parse_uuid(value: &str, what: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(value).map_err(|_| format!("Invalid {}: {}", what, value))
+}
+
+fn validate_deck(name: &str, cards: &[DeckCard]) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Deck name cannot be empty".to_string());
+    }
+    if let Some(card) = cards.iter().find(|c| c.name.trim().is_empty() || c.quantity <= 0) {
+        return Err(format!("Invalid card entry: '{}' x{}", card.name, card.quantity));
+    }
+    Ok(())
+}
+
+async fn insert_deck_cards(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    deck_id: Uuid,
+    cards: &[DeckCard],
+) -> Result<(), String> {
+    for (position, card) in cards.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO deck_cards (deck_id, position, name, set_code, collector_number, quantity, is_commander)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(deck_id)
+        .bind(position as i32)
+        .bind(&card.name)
+        .bind(&card.set_code)
+        .bind(&card.collector_number)
+        .bind(card.quantity)
+        .bind(card.is_commander)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to save deck cards: {}", e))?;
+    }
+    Ok(())
+}
+
+pub async fn create_deck(
+    pool: &PgPool,
+    user_id: &str,
+    name: &str,
+    cards: &[DeckCard],
+) -> Result<Deck, String> {
+    validate_deck(name, cards)?;
+    let user_uuid = parse_uuid(user_id, "user id")?;
+    let deck_id = Uuid::new_v4();
+
+    let mut tx = pool.begin().await.map_err(|e| format!("Database error: {}", e))?;
+
+    sqlx::query("INSERT INTO decks (id, user_id, name) VALUES ($1, $2, $3)")
+        .bind(deck_id)
+        .bind(user_uuid)
+        .bind(name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to create deck: {}", e))?;
+
+    insert_deck_cards(&mut tx, deck_id, cards).await?;
+
+    tx.commit().await.map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(Deck {
+        id: deck_id.to_string(),
+        user_id: user_id.to_string(),
+        name: name.to_string(),
+        cards: cards.to_vec(),
+    })
+}
+
+pub async fn list_decks(pool: &PgPool, user_id: &str) -> Result<Vec<DeckSummary>, String> {
+    let user_uuid = parse_uuid(user_id, "user id")?;
+
+    let rows = sqlx::query(
+        "SELECT d.id, d.name, COALESCE(SUM(c.quantity), 0)::BIGINT AS card_count
+         FROM decks d
+         LEFT JOIN deck_cards c ON c.deck_id = d.id
+         WHERE d.user_id = $1
+         GROUP BY d.id, d.name, d.updated_at
+         ORDER BY d.updated_at DESC",
+    )
+    .bind(user_uuid)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DeckSummary {
+            id: row.get::<Uuid, _>("id").to_string(),
+            name: row.get("name"),
+            card_count: row.get("card_count"),
+        })
+        .collect())
+}
+
+/// Fetches a deck owned by `user_id`. Other users' decks are reported as not found.
+pub async fn get_deck(pool: &PgPool, user_id: &str, deck_id: &str) -> Result<Deck, String> {
+    let user_uuid = parse_uuid(user_id, "user id")?;
+    let deck_uuid = parse_uuid(deck_id, "deck id")?;
+
+    let row = sqlx::query("SELECT id, user_id, name FROM decks WHERE id = $1 AND user_id = $2")
+        .bind(deck_uuid)
+        .bind(user_uuid)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or("Deck not found")?;
+
+    let cards = sqlx::query_as::<_, (String, Option<String>, Option<String>, i32, bool)>(
+        "SELECT name, set_code, collector_number, quantity, is_commander
+         FROM deck_cards WHERE deck_id = $1 ORDER BY position",
+    )
+    .bind(deck_uuid)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?
+    .into_iter()
+    .map(|(name, set_code, collector_number, quantity, is_commander)| DeckCard {
+        name,
+        set_code,
+        collector_number,
+        quantity,
+        is_commander,
+    })
+    .collect();
+
+    Ok(Deck {
+        id: row.get::<Uuid, _>("id").to_string(),
+        user_id: row.get::<Uuid, _>("user_id").to_string(),
+        name: row.get("name"),
+        cards,
+    })
+}
+
+pub async fn update_deck(
+    pool: &PgPool,
+    user_id: &str,
+    deck_id: &str,
+    name: &str,
+    cards: &[DeckCard],
+) -> Result<Deck, String> {
+    validate_deck(name, cards)?;
+    let user_uuid = parse_uuid(user_id, "user id")?;
+    let deck_uuid = parse_uuid(deck_id, "deck id")?;
+
+    let mut tx = pool.begin().await.map_err(|e| format!("Database error: {}", e))?;
+
+    let result = sqlx::query("UPDATE decks SET name = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2 AND user_id = $3")
+        .bind(name)
+        .bind(deck_uuid)
+        .bind(user_uuid)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err("Deck not found".to_string());
+    }
+
+    sqlx::query("DELETE FROM deck_cards WHERE deck_id = $1")
+        .bind(deck_uuid)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    insert_deck_cards(&mut tx, deck_uuid, cards).await?;
+
+    tx.commit().await.map_err(|e| format!("Database error: {}", e))?;
+
+    get_deck(pool, user_id, deck_id).await
+}
+
+pub async fn delete_deck(pool: &PgPool, user_id: &str, deck_id: &str) -> Result<(), String> {
+    let user_uuid = parse_uuid(user_id, "user id")?;
+    let deck_uuid = parse_uuid(deck_id, "deck id")?;
+
+    let result = sqlx::query("DELETE FROM decks WHERE id = $1 AND user_id = $2")
+        .bind(deck_uuid)
+        .bind(user_uuid)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    if result.rows_affected() == 0 {
+        Err("Deck not found".to_string())
+    } else {
+        Ok(())
+    }
+}
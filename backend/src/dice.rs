@@ -0,0 +1,165 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Most dice or coins a single roll may throw.
+pub const MAX_DICE: u32 = 100;
+
+/// Most sides a die may have.
+pub const MAX_SIDES: u32 = 1000;
+
+/// Largest modifier accepted in notation like `3d6+1`.
+pub const MAX_MODIFIER: i64 = 1000;
+
+/// Faces of the planar die: one Planeswalk, one Chaos and four blanks.
+const PLANAR_FACES: [&str; 6] = ["Planeswalk", "Chaos", "Blank", "Blank", "Blank", "Blank"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RollSpec {
+    Dice { count: u32, sides: u32, modifier: i64 },
+    Coins { count: u32 },
+    Planar,
+}
+
+/// The result of a roll made by the server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RollOutcome {
+    /// The roll in normalized notation, e.g. `3d6+1`, `d20`, `coin` or `planar`.
+    pub notation: String,
+    /// Each die or coin on its own.
+    pub rolls: Vec<String>,
+    /// The total for dice, the face for a single coin or planar die.
+    pub result: String,
+}
+
+/// Parses dice notation (`3d6+1`, `d20`, `2d8-1`), `coin`/`3 coins` or `planar`.
+pub fn parse(notation: &str) -> Result<RollSpec, String> {
+    let text: String = notation.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+
+    if text == "planar" || text == "planardie" {
+        return Ok(RollSpec::Planar);
+    }
+
+    for word in ["coins", "coin"] {
+        if let Some(count) = text.strip_suffix(word) {
+            let count = parse_count(count)?;
+            return Ok(RollSpec::Coins { count });
+        }
+    }
+
+    let (count, rest) = text.split_once('d').ok_or(format!("Can't roll \"{}\"", notation))?;
+    let count = parse_count(count)?;
+    let (sides, modifier) = match rest.find(['+', '-']) {
+        Some(index) => {
+            let modifier: i64 = rest[index..]
+                .parse()
+                .map_err(|_| format!("Invalid modifier in \"{}\"", notation))?;
+            (&rest[..index], modifier)
+        }
+        None => (rest, 0),
+    };
+    let sides: u32 = sides.parse().map_err(|_| format!("Invalid number of sides in \"{}\"", notation))?;
+
+    if !(2..=MAX_SIDES).contains(&sides) {
+        return Err(format!("Dice need 2 to {} sides", MAX_SIDES));
+    }
+    if modifier.abs() > MAX_MODIFIER {
+        return Err(format!("Modifiers can be at most {}", MAX_MODIFIER));
+    }
+    Ok(RollSpec::Dice { count, sides, modifier })
+}
+
+/// An empty count means one, as in `d20` or `coin`.
+fn parse_count(count: &str) -> Result<u32, String> {
+    if count.is_empty() {
+        return Ok(1);
+    }
+    match count.parse::<u32>() {
+        Ok(n) if (1..=MAX_DICE).contains(&n) => Ok(n),
+        _ => Err(format!("You can roll 1 to {} at a time", MAX_DICE)),
+    }
+}
+
+impl RollSpec {
+    fn notation(&self) -> String {
+        match self {
+            RollSpec::Dice { count, sides, modifier } => {
+                let count = if *count == 1 { String::new() } else { count.to_string() };
+                match modifier {
+                    0 => format!("{}d{}", count, sides),
+                    m => format!("{}d{}{:+}", count, sides, m),
+                }
+            }
+            RollSpec::Coins { count: 1 } => "coin".to_string(),
+            RollSpec::Coins { count } => format!("{} coins", count),
+            RollSpec::Planar => "planar".to_string(),
+        }
+    }
+
+    pub fn roll<R: Rng>(&self, rng: &mut R) -> RollOutcome {
+        let (rolls, result) = match self {
+            RollSpec::Dice { count, sides, modifier } => {
+                let values: Vec<i64> = (0..*count).map(|_| rng.gen_range(1..=*sides) as i64).collect();
+                let total = values.iter().sum::<i64>() + modifier;
+                (values.iter().map(|v| v.to_string()).collect(), total.to_string())
+            }
+            RollSpec::Coins { count } => {
+                let flips: Vec<String> = (0..*count)
+                    .map(|_| if rng.gen_bool(0.5) { "Heads" } else { "Tails" }.to_string())
+                    .collect();
+                let result = match flips.as_slice() {
+                    [single] => single.clone(),
+                    _ => {
+                        let heads = flips.iter().filter(|f| *f == "Heads").count();
+                        format!("{} heads, {} tails", heads, flips.len() - heads)
+                    }
+                };
+                (flips, result)
+            }
+            RollSpec::Planar => {
+                let face = PLANAR_FACES[rng.gen_range(0..PLANAR_FACES.len())].to_string();
+                (vec![face.clone()], face)
+            }
+        };
+
+        RollOutcome {
+            notation: self.notation(),
+            rolls,
+            result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn parses_dice_notation() {
+        assert_eq!(parse("3d6+1"), Ok(RollSpec::Dice { count: 3, sides: 6, modifier: 1 }));
+        assert_eq!(parse("D20"), Ok(RollSpec::Dice { count: 1, sides: 20, modifier: 0 }));
+        assert_eq!(parse("2d8 - 2"), Ok(RollSpec::Dice { count: 2, sides: 8, modifier: -2 }));
+        assert_eq!(parse("coin"), Ok(RollSpec::Coins { count: 1 }));
+        assert_eq!(parse("3 coins"), Ok(RollSpec::Coins { count: 3 }));
+        assert_eq!(parse("planar"), Ok(RollSpec::Planar));
+
+        for bad in ["", "d", "d1", "0d6", "101d6", "d6+x", "banana", "2d6+5000"] {
+            assert!(parse(bad).is_err(), "{} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn rolls_stay_in_range() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..200 {
+            let outcome = parse("3d6+1").unwrap().roll(&mut rng);
+            let total: i64 = outcome.result.parse().unwrap();
+            assert!((4..=19).contains(&total));
+            assert_eq!(outcome.rolls.len(), 3);
+            assert_eq!(outcome.notation, "3d6+1");
+        }
+        let planar = RollSpec::Planar.roll(&mut rng);
+        assert!(PLANAR_FACES.contains(&planar.result.as_str()));
+    }
+}
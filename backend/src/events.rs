@@ -0,0 +1,612 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dice::RollOutcome;
+use crate::game::{Card, GameSession, Player, Zone};
+use crate::websocket_new::Message;
+
+/// Oldest events are dropped once a game's log grows past this many entries.
+pub const EVENT_LOG_LIMIT: usize = 5000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EventKind {
+    UpdateLife,
+    UpdateCounter,
+    UpdateCommanderDamage,
+    Concede,
+    Reinstate,
+    SetPlayerName,
+    DiceRoll,
+    LoadLibrary,
+    ShuffleLibrary,
+    MoveCard,
+    DrawCard,
+    MillCard,
+    TapCard,
+    FlipCard,
+    Copy,
+    UpdateCardCounter,
+    GainControl,
+    ReturnControl,
+    CastSpell,
+    ResolveStack,
+    CounterSpell,
+    ReorderStack,
+    SpawnSharedCard,
+    RemoveSharedCard,
+    AttachCard,
+    DetachCard,
+    UntapAll,
+    MoveCardOnBattlefield,
+    NextTurn,
+    UndoTurn,
+    AdvanceStep,
+    SetTurnAutomation,
+    RandomizeStartingPlayer,
+    ReorderSeats,
+    Undo,
+    StartMulligans,
+    Mulligan,
+    KeepHand,
+    RestartGame,
+    LeaveTable,
+    RevealCard,
+    ScryComplete,
+    SurveilComplete,
+    ManifestCard,
+    TutorCard,
+    PutInLibrary,
+    ExileTopFaceDown,
+    RevealUntil,
+    SpawnCard,
+    CreateRelatedToken,
+    FlipCardFace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameEvent {
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    pub actor_id: String,
+    pub kind: EventKind,
+    /// What everyone at the table is allowed to know.
+    pub summary: String,
+    /// The full description including hidden cards, only shown to the actor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private_summary: Option<String>,
+}
+
+impl GameEvent {
+    /// Returns the event as `viewer_id` may see it.
+    pub fn view_for(&self, viewer_id: &str) -> GameEvent {
+        let mut view = self.clone();
+        if view.actor_id != viewer_id {
+            view.private_summary = None;
+        }
+        view
+    }
+}
+
+/// A described action that hasn't been assigned a sequence number yet.
+#[derive(Debug, Clone)]
+pub struct EventDraft {
+    pub kind: EventKind,
+    pub summary: String,
+    pub private_summary: Option<String>,
+}
+
+impl EventDraft {
+    fn public(kind: EventKind, summary: String) -> Self {
+        Self { kind, summary, private_summary: None }
+    }
+
+    fn private(kind: EventKind, summary: String, private_summary: String) -> Self {
+        Self { kind, summary, private_summary: Some(private_summary) }
+    }
+}
+
+fn zone_label(zone: &Zone) -> &'static str {
+    match zone {
+        Zone::Hand => "hand",
+        Zone::Battlefield => "battlefield",
+        Zone::Graveyard => "graveyard",
+        Zone::Exile => "exile",
+        Zone::CommandZone => "command zone",
+        Zone::Library => "library",
+    }
+}
+
+/// Whether a card in this zone can be identified by everyone at the table.
+fn is_public(card: &Card, zone: &Zone) -> bool {
+    if card.is_revealed {
+        return true;
+    }
+    match zone {
+        Zone::Hand | Zone::Library => false,
+        Zone::Battlefield | Zone::Exile => !card.is_flipped,
+        Zone::Graveyard | Zone::CommandZone => true,
+    }
+}
+
+fn public_name(card: &Card, zone: &Zone) -> String {
+    if is_public(card, zone) {
+        card.name.clone()
+    } else if card.is_flipped && *zone != Zone::Hand && *zone != Zone::Library {
+        "a face-down card".to_string()
+    } else {
+        "a card".to_string()
+    }
+}
+
+fn possessive(actor: &Player, target: &Player) -> String {
+    if actor.id == target.id {
+        "their".to_string()
+    } else {
+        format!("{}'s", target.name)
+    }
+}
+
+fn find_card<'a>(player: &'a Player, card_id: &str) -> Option<(&'a Card, Zone)> {
+    [Zone::Hand, Zone::Battlefield, Zone::Graveyard, Zone::Exile, Zone::CommandZone, Zone::Library]
+        .into_iter()
+        .find_map(|zone| {
+            player.get_zone(&zone)
+                .iter()
+                .find(|c| c.id == card_id)
+                .map(|card| (card, zone))
+        })
+}
+
+/// Finds a card on any battlefield, along with the player controlling it.
+fn find_permanent<'a>(game: &'a GameSession, card_id: &str) -> Option<(&'a Card, &'a Player)> {
+    game.players.values().find_map(|p| {
+        p.battlefield.iter().find(|c| c.id == card_id).map(|c| (c, p))
+    })
+}
+
+/// "1st", "2nd", "3rd", "4th", ... for library positions.
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+fn name_list(cards: &[&Card]) -> String {
+    cards.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// Describes a roll the server made. Rolls are logged by their handler since
+/// the result isn't known until after the message is applied.
+pub fn describe_roll(player_name: &str, outcome: &RollOutcome) -> EventDraft {
+    let detail = if outcome.rolls.len() > 1 {
+        format!(" ({})", outcome.rolls.join(", "))
+    } else {
+        String::new()
+    };
+    EventDraft::public(
+        EventKind::DiceRoll,
+        format!("{} rolled {}: {}{}", player_name, outcome.notation, outcome.result, detail),
+    )
+}
+
+/// Describes what `msg` is about to do, using the state before it is applied.
+/// Returns `None` for messages that aren't logged or that refer to cards or
+/// players that don't exist (those won't change anything either).
+pub fn describe(game: &GameSession, actor_id: &str, msg: &Message) -> Option<EventDraft> {
+    let actor = game.get_player(actor_id)?;
+    let name = &actor.name;
+
+    let draft = match msg {
+        Message::UpdateLife { player_id, delta } => {
+            let target = game.get_player(player_id)?;
+            EventDraft::public(
+                EventKind::UpdateLife,
+                format!("{} changed {} life by {:+} ({} → {})", name, possessive(actor, target), delta, target.life, target.life + delta),
+            )
+        }
+        Message::UpdateCounter { player_id, counter_type, delta } => {
+            let target = game.get_player(player_id)?;
+            EventDraft::public(
+                EventKind::UpdateCounter,
+                format!("{} changed {} {} by {:+}", name, possessive(actor, target), counter_type, delta),
+            )
+        }
+        Message::Concede {} => EventDraft::public(EventKind::Concede, format!("{} conceded", name)),
+        Message::Reinstate { player_id } => {
+            let target = game.get_player(player_id)?;
+            EventDraft::public(EventKind::Reinstate, format!("{} brought {} back into the game", name, target.name))
+        }
+        Message::UpdateCommanderDamage { player_id, commander_id, delta } => {
+            let target = game.get_player(player_id)?;
+            let commander = &game.find_commander(commander_id)?.name;
+            let before = game.commander_damage.get(player_id).and_then(|d| d.get(commander_id)).copied().unwrap_or(0);
+            let victim = if actor.id == target.id { "themselves" } else { target.name.as_str() };
+            EventDraft::public(
+                EventKind::UpdateCommanderDamage,
+                format!("{} changed commander damage from {} to {} by {:+} ({} total)", name, commander, victim, delta, (before + delta).max(0)),
+            )
+        }
+        Message::SetPlayerName { name: new_name, .. } => {
+            if new_name == name {
+                return None;
+            }
+            EventDraft::public(EventKind::SetPlayerName, format!("{} is now named {}", name, new_name))
+        }
+        Message::LoadLibrary { .. } | Message::LoadDeck { .. } => {
+            EventDraft::public(EventKind::LoadLibrary, format!("{} loaded a deck", name))
+        }
+        Message::ShuffleLibrary { .. } => {
+            EventDraft::public(EventKind::ShuffleLibrary, format!("{} shuffled their library", name))
+        }
+        Message::MoveCard { card_id, from_zone, to_zone, .. } => {
+            let from = Zone::from_name(from_zone).unwrap_or(Zone::Hand);
+            let to = Zone::from_name(to_zone).unwrap_or(Zone::Hand);
+            let card = actor.get_zone(&from).iter().find(|c| &c.id == card_id)?;
+            let summary = |card_name: &str| {
+                format!("{} moved {} from their {} to their {}", name, card_name, zone_label(&from), zone_label(&to))
+            };
+
+            // A card entering a public zone face up is public from then on
+            if is_public(card, &from) || is_public(card, &to) {
+                EventDraft::public(EventKind::MoveCard, summary(&card.name))
+            } else {
+                EventDraft::private(EventKind::MoveCard, summary(&public_name(card, &to)), summary(&card.name))
+            }
+        }
+        Message::DrawCard { count, .. } => {
+            let count = count.unwrap_or(1).min(actor.library.len());
+            if count == 0 {
+                return None;
+            }
+            // The top of the library is the end
+            let drawn: Vec<&Card> = actor.library.iter().rev().take(count).collect();
+            let plural = if count == 1 { "card" } else { "cards" };
+            EventDraft::private(
+                EventKind::DrawCard,
+                format!("{} drew {} {}", name, count, plural),
+                format!("{} drew {}", name, name_list(&drawn)),
+            )
+        }
+        Message::MillCard { .. } => {
+            let card = actor.library.last()?;
+            EventDraft::public(EventKind::MillCard, format!("{} milled {}", name, card.name))
+        }
+        Message::TapCard { card_id, .. } => {
+            let card = actor.battlefield.iter().find(|c| &c.id == card_id)?;
+            let verb = if card.is_tapped { "untapped" } else { "tapped" };
+            EventDraft::public(EventKind::TapCard, format!("{} {} {}", name, verb, public_name(card, &Zone::Battlefield)))
+        }
+        Message::FlipCard { card_id, .. } => {
+            let (card, zone) = find_card(actor, card_id)?;
+            let summary = if card.is_flipped {
+                format!("{} turned {} face up", name, card.name)
+            } else {
+                format!("{} turned {} face down", name, public_name(card, &zone))
+            };
+            EventDraft::public(EventKind::FlipCard, summary)
+        }
+        Message::Copy { card_id, .. } => {
+            let card = actor.battlefield.iter().find(|c| &c.id == card_id)?;
+            EventDraft::public(EventKind::Copy, format!("{} created a token copy of {}", name, public_name(card, &Zone::Battlefield)))
+        }
+        Message::UpdateCardCounter { player_id, card_id, counter_type, delta } => {
+            let target = game.get_player(player_id)?;
+            let card = target.battlefield.iter().find(|c| &c.id == card_id)?;
+            let (verb, preposition) = if *delta >= 0 { ("put", "on") } else { ("removed", "from") };
+            EventDraft::public(
+                EventKind::UpdateCardCounter,
+                format!("{} {} {} {} counter(s) {} {}", name, verb, delta.abs(), counter_type.trim(), preposition, public_name(card, &Zone::Battlefield)),
+            )
+        }
+        Message::CastSpell { card_id, from_zone, .. } => {
+            let zone = Zone::from_name(from_zone)?;
+            let card = actor.get_zone(&zone).iter().find(|c| &c.id == card_id)?;
+            let summary = |card_name: &str| format!("{} cast {} from their {}", name, card_name, zone_label(&zone));
+            if card.is_flipped {
+                EventDraft::private(EventKind::CastSpell, summary("a face-down spell"), summary(&card.name))
+            } else {
+                EventDraft::public(EventKind::CastSpell, summary(&card.name))
+            }
+        }
+        Message::ResolveStack {} => {
+            let item = game.stack.last()?;
+            // Face-down permanents stay hidden; anything else ends up face up in a graveyard
+            let card_name = if item.card.is_flipped && item.is_permanent { "a face-down spell" } else { item.card.name.as_str() };
+            EventDraft::public(EventKind::ResolveStack, format!("{} resolved {}", name, card_name))
+        }
+        Message::CounterSpell { card_id } => {
+            let item = game.stack.iter().find(|item| &item.card.id == card_id)?;
+            EventDraft::public(EventKind::CounterSpell, format!("{} countered {}", name, item.card.name))
+        }
+        Message::ReorderStack { .. } => {
+            EventDraft::public(EventKind::ReorderStack, format!("{} reordered the stack", name))
+        }
+        Message::SpawnSharedCard { card_name, .. } => {
+            EventDraft::public(EventKind::SpawnSharedCard, format!("{} put {} in the shared area", name, card_name))
+        }
+        Message::RemoveSharedCard { card_id } => {
+            let card = game.battlefield.iter().find(|c| &c.id == card_id)?;
+            EventDraft::public(EventKind::RemoveSharedCard, format!("{} removed {} from the shared area", name, card.name))
+        }
+        Message::GainControl { card_id, controller_id } => {
+            let (card, from) = find_permanent(game, card_id)?;
+            let to = game.get_player(controller_id)?;
+            let card_name = format!("{} {}", possessive(actor, from), public_name(card, &Zone::Battlefield));
+            let summary = if to.id == actor.id {
+                format!("{} gained control of {}", name, card_name)
+            } else {
+                format!("{} gave control of {} to {}", name, card_name, to.name)
+            };
+            EventDraft::public(EventKind::GainControl, summary)
+        }
+        Message::ReturnControl { card_id } => {
+            let (card, _) = find_permanent(game, card_id)?;
+            let owner = game.get_player(card.owner_id.as_deref()?)?;
+            EventDraft::public(
+                EventKind::ReturnControl,
+                format!("{} returned {} to {}", name, public_name(card, &Zone::Battlefield), owner.name),
+            )
+        }
+        Message::AttachCard { card_id, host_id, .. } => {
+            let card = actor.battlefield.iter().find(|c| &c.id == card_id)?;
+            let (host, host_owner) = find_permanent(game, host_id)?;
+            EventDraft::public(
+                EventKind::AttachCard,
+                format!("{} attached {} to {} {}", name, public_name(card, &Zone::Battlefield), possessive(actor, host_owner), public_name(host, &Zone::Battlefield)),
+            )
+        }
+        Message::DetachCard { card_id, .. } => {
+            let card = actor.battlefield.iter().find(|c| &c.id == card_id)?;
+            EventDraft::public(EventKind::DetachCard, format!("{} unattached {}", name, public_name(card, &Zone::Battlefield)))
+        }
+        Message::UntapAll { .. } => {
+            EventDraft::public(EventKind::UntapAll, format!("{} untapped all their permanents", name))
+        }
+        Message::MoveCardOnBattlefield { card_id, .. } => {
+            let card = actor.battlefield.iter().find(|c| &c.id == card_id)?;
+            EventDraft::public(
+                EventKind::MoveCardOnBattlefield,
+                format!("{} moved {} on the battlefield", name, public_name(card, &Zone::Battlefield)),
+            )
+        }
+        Message::NextTurn {} => EventDraft::public(EventKind::NextTurn, format!("{} passed the turn", name)),
+        Message::UndoTurn {} => EventDraft::public(EventKind::UndoTurn, format!("{} undid the last turn change", name)),
+        Message::AdvanceStep {} => match game.step.next() {
+            Some(step) => EventDraft::public(EventKind::AdvanceStep, format!("{} moved to the {} step", name, step.label())),
+            None => EventDraft::public(EventKind::NextTurn, format!("{} passed the turn", name)),
+        },
+        Message::SetTurnAutomation { auto_untap, auto_draw } => {
+            let state = |on: bool| if on { "on" } else { "off" };
+            EventDraft::public(
+                EventKind::SetTurnAutomation,
+                format!("{} turned automatic untap {} and automatic draw {}", name, state(*auto_untap), state(*auto_draw)),
+            )
+        }
+        Message::RandomizeStartingPlayer {} => EventDraft::public(
+            EventKind::RandomizeStartingPlayer,
+            format!("{} picked a random starting player", name),
+        ),
+        Message::ReorderSeats { seats } => {
+            let names: Vec<&str> = seats
+                .iter()
+                .map(|id| game.get_player(id).map(|p| p.name.as_str()).unwrap_or("Unknown"))
+                .collect();
+            EventDraft::public(EventKind::ReorderSeats, format!("{} changed the seating to {}", name, names.join(", ")))
+        }
+        Message::Undo { count } => {
+            let count = count.unwrap_or(1);
+            let plural = if count == 1 { "action" } else { "actions" };
+            EventDraft::public(EventKind::Undo, format!("{} undid the last {} {}", name, count, plural))
+        }
+        Message::UndoVote { approve: true } => {
+            let pending = game.pending_undo.as_ref()?;
+            let requester = game.get_player(&pending.requester_id).map(|p| p.name.as_str()).unwrap_or("Unknown");
+            EventDraft::public(EventKind::Undo, format!("{} approved {}'s undo of {} actions", name, requester, pending.count))
+        }
+        Message::StartMulligans { .. } => {
+            EventDraft::public(EventKind::StartMulligans, format!("{} dealt opening hands", name))
+        }
+        Message::Mulligan {} => {
+            let taken = game.mulligan.as_ref()?.decisions.get(actor_id)?.mulligans;
+            EventDraft::public(EventKind::Mulligan, format!("{} took mulligan #{}", name, taken + 1))
+        }
+        Message::KeepHand { bottom_cards } => {
+            let bottom: Vec<&Card> = bottom_cards.iter().filter_map(|id| actor.hand.iter().find(|c| &c.id == id)).collect();
+            let kept = actor.hand.len().saturating_sub(bottom.len());
+            EventDraft::private(
+                EventKind::KeepHand,
+                format!("{} kept {} cards, putting {} on the bottom", name, kept, bottom.len()),
+                format!("{} kept {} cards, putting [{}] on the bottom", name, kept, name_list(&bottom)),
+            )
+        }
+        Message::RestartGame {} => EventDraft::public(EventKind::RestartGame, format!("{} restarted the game", name)),
+        Message::LeaveTable {} => EventDraft::public(EventKind::LeaveTable, format!("{} left the table", name)),
+        Message::RevealCard { card_id, zone, .. } => {
+            let zone = Zone::from_name(zone)?;
+            let card = actor.get_zone(&zone).iter().find(|c| &c.id == card_id)?;
+            EventDraft::public(
+                EventKind::RevealCard,
+                format!("{} revealed {} from their {}", name, card.name, zone_label(&zone)),
+            )
+        }
+        Message::ScryComplete { top_cards, bottom_cards, .. } => {
+            let lookup = |ids: &[String]| -> Vec<&Card> {
+                ids.iter().filter_map(|id| actor.library.iter().find(|c| &c.id == id)).collect()
+            };
+            let top = lookup(top_cards);
+            let bottom = lookup(bottom_cards);
+            EventDraft::private(
+                EventKind::ScryComplete,
+                format!("{} scried {}: {} on top, {} on the bottom", name, top.len() + bottom.len(), top.len(), bottom.len()),
+                format!("{} scried: top [{}], bottom [{}]", name, name_list(&top), name_list(&bottom)),
+            )
+        }
+        Message::SurveilComplete { top_cards, graveyard_cards, .. } => {
+            let lookup = |ids: &[String]| -> Vec<&Card> {
+                ids.iter().filter_map(|id| actor.library.iter().find(|c| &c.id == id)).collect()
+            };
+            let top = lookup(top_cards);
+            let graveyard = lookup(graveyard_cards);
+            // Cards put into the graveyard are public, the ones kept on top are not
+            EventDraft::private(
+                EventKind::SurveilComplete,
+                format!("{} surveilled {}: {} on top, [{}] into the graveyard", name, top.len() + graveyard.len(), top.len(), name_list(&graveyard)),
+                format!("{} surveilled: top [{}], graveyard [{}]", name, name_list(&top), name_list(&graveyard)),
+            )
+        }
+        Message::ManifestCard { card_id, .. } => {
+            let card = actor.library.iter().find(|c| &c.id == card_id)?;
+            EventDraft::private(
+                EventKind::ManifestCard,
+                format!("{} manifested a card from their library", name),
+                format!("{} manifested {} from their library", name, card.name),
+            )
+        }
+        Message::TutorCard { card_id, to_zone, reveal, .. } => {
+            let card = actor.library.iter().find(|c| &c.id == card_id)?;
+            let zone = Zone::from_name(to_zone).filter(|z| *z != Zone::Library)?;
+            let destination = match zone {
+                Zone::Battlefield => "onto the battlefield".to_string(),
+                ref other => format!("into their {}", zone_label(other)),
+            };
+            let public = *reveal || matches!(zone, Zone::Battlefield | Zone::Graveyard | Zone::Exile);
+            let private = format!("{} searched their library and put {} {}", name, card.name, destination);
+            if public {
+                EventDraft::public(EventKind::TutorCard, private)
+            } else {
+                EventDraft::private(
+                    EventKind::TutorCard,
+                    format!("{} searched their library and put a card {}", name, destination),
+                    private,
+                )
+            }
+        }
+        Message::PutInLibrary { card_id, from_zone, position, from_bottom, .. } => {
+            let zone = Zone::from_name(from_zone)?;
+            let card = actor.get_zone(&zone).iter().find(|c| &c.id == card_id)?;
+            let place = match (*position, *from_bottom) {
+                (1, false) => "on top of".to_string(),
+                (1, true) => "on the bottom of".to_string(),
+                (n, false) => format!("{} from the top of", ordinal(n)),
+                (n, true) => format!("{} from the bottom of", ordinal(n)),
+            };
+            let summary = |card_name: &str| format!("{} put {} from their {} {} their library", name, card_name, zone_label(&zone), place);
+            EventDraft::private(EventKind::PutInLibrary, summary(&public_name(card, &zone)), summary(&card.name))
+        }
+        Message::ExileTopFaceDown { count, .. } => {
+            let exiled: Vec<&Card> = actor.library.iter().rev().take(*count).collect();
+            if exiled.is_empty() {
+                return None;
+            }
+            EventDraft::private(
+                EventKind::ExileTopFaceDown,
+                format!("{} exiled the top {} cards of their library face down", name, exiled.len()),
+                format!("{} exiled [{}] face down", name, name_list(&exiled)),
+            )
+        }
+        Message::RevealUntil { card_name, .. } => {
+            let hit = actor.library.iter().rev().position(|c| c.name.eq_ignore_ascii_case(card_name.trim()));
+            let revealed: Vec<&Card> = actor.library.iter().rev().take(hit.map_or(actor.library.len(), |i| i + 1)).collect();
+            if revealed.is_empty() {
+                return None;
+            }
+            let outcome = match hit {
+                Some(_) => format!("found {}", card_name.trim()),
+                None => "found nothing".to_string(),
+            };
+            EventDraft::public(
+                EventKind::RevealUntil,
+                format!("{} revealed [{}] from their library and {}", name, name_list(&revealed), outcome),
+            )
+        }
+        Message::SpawnCard { card_name, .. } => {
+            EventDraft::public(EventKind::SpawnCard, format!("{} created {}", name, card_name))
+        }
+        Message::CreateRelatedToken { card_id, token_name, count, .. } => {
+            let card = actor.battlefield.iter().find(|c| &c.id == card_id)?;
+            let count = count.unwrap_or(1);
+            let what = match token_name {
+                Some(token) => format!("{} {} token{}", count, token, if count == 1 { "" } else { "s" }),
+                None => format!("{} token{}", count, if count == 1 { "" } else { "s" }),
+            };
+            EventDraft::public(EventKind::CreateRelatedToken, format!("{} created {} from {}", name, what, public_name(card, &Zone::Battlefield)))
+        }
+        Message::FlipCardFace { card_id, .. } => {
+            let (card, zone) = find_card(actor, card_id)?;
+            if !card.is_two_sided {
+                return None;
+            }
+            EventDraft::public(EventKind::FlipCardFace, format!("{} transformed {}", name, public_name(card, &zone)))
+        }
+        _ => return None,
+    };
+
+    Some(draft)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn game_with_hand(card: Card) -> GameSession {
+        let mut game = GameSession::new("g".to_string());
+        let mut player = Player::new("a".to_string(), "Alice".to_string(), 0);
+        player.hand.push(card);
+        game.add_player(player);
+        game
+    }
+
+    fn card(id: &str, is_flipped: bool) -> Card {
+        Card {
+            id: id.to_string(),
+            name: "Secret Morph".to_string(),
+            is_tapped: false,
+            is_flipped,
+            is_commander: false,
+            is_token: false,
+            is_two_sided: false,
+            is_back_face: false,
+            set_code: None,
+            collector_number: None,
+            position_x: 0.0,
+            position_y: 0.0,
+            is_revealed: false,
+            counters: BTreeMap::new(),
+            attached_to: None,
+            is_aura: false,
+            owner_id: None,
+        }
+    }
+
+    fn move_to_battlefield(id: &str) -> Message {
+        Message::MoveCard {
+            card_id: id.to_string(),
+            from_zone: "hand".to_string(),
+            to_zone: "battlefield".to_string(),
+            position_x: None,
+            position_y: None,
+        }
+    }
+
+    #[test]
+    fn face_down_cards_entering_the_battlefield_stay_hidden() {
+        let game = game_with_hand(card("x", true));
+        let draft = describe(&game, "a", &move_to_battlefield("x")).unwrap();
+        assert!(!draft.summary.contains("Secret Morph"));
+        assert!(draft.summary.contains("a face-down card"));
+        assert!(draft.private_summary.unwrap().contains("Secret Morph"));
+    }
+
+    #[test]
+    fn face_up_cards_entering_the_battlefield_are_public() {
+        let game = game_with_hand(card("x", false));
+        let draft = describe(&game, "a", &move_to_battlefield("x")).unwrap();
+        assert!(draft.summary.contains("Secret Morph"));
+        assert!(draft.private_summary.is_none());
+    }
+}
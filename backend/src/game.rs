@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 use uuid::Uuid;
 use tokio::sync::broadcast;
 
+use crate::events::{EventDraft, GameEvent, EVENT_LOG_LIMIT};
+use crate::tokens::{RelatedToken, MAX_TOKENS_PER_REQUEST};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Card {
     pub id: String,
@@ -17,6 +21,64 @@ pub struct Card {
     pub collector_number: Option<String>,
     pub position_x: f32,
     pub position_y: f32,
+    #[serde(default)]
+    pub is_revealed: bool,
+    /// Counters on a permanent by kind ("+1/+1", "loyalty", ...).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counters: BTreeMap<String, i32>,
+    /// Id of the battlefield card this aura or equipment is attached to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attached_to: Option<String>,
+    /// Auras are put into the graveyard when their host leaves the battlefield;
+    /// anything else just falls off.
+    #[serde(default)]
+    pub is_aura: bool,
+    /// Set while someone other than the card's owner controls it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+}
+
+/// Where an attachment is placed relative to its host.
+pub const ATTACHMENT_OFFSET: (f32, f32) = (15.0, 15.0);
+
+/// How far apart newly created tokens are placed from the card that made them.
+pub const TOKEN_OFFSET: (f32, f32) = (20.0, 20.0);
+
+/// Longest counter name accepted from a client.
+pub const MAX_COUNTER_NAME_LEN: usize = 32;
+
+impl Card {
+    /// Returns a card back that only keeps what is visible from across the table.
+    pub fn redacted(&self) -> Card {
+        Card {
+            id: self.id.clone(),
+            name: String::new(),
+            is_tapped: self.is_tapped,
+            is_flipped: true,
+            is_commander: false,
+            is_token: false,
+            is_two_sided: false,
+            is_back_face: false,
+            set_code: None,
+            collector_number: None,
+            position_x: self.position_x,
+            position_y: self.position_y,
+            is_revealed: false,
+            // Counters and attachments stay visible on face-down permanents
+            counters: self.counters.clone(),
+            attached_to: self.attached_to.clone(),
+            is_aura: false,
+            owner_id: self.owner_id.clone(),
+        }
+    }
+
+    /// Resets what only lasts while the card is on the battlefield.
+    pub fn leave_battlefield(&mut self) {
+        self.counters.clear();
+        self.attached_to = None;
+        self.is_aura = false;
+        self.owner_id = None;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -25,10 +87,115 @@ pub enum Zone {
     Battlefield,
     Graveyard,
     Exile,
+    // Named after the game's own term, and matched by the client as-is
+    #[allow(clippy::enum_variant_names)]
     CommandZone,
     Library,
 }
 
+impl Zone {
+    /// Parses the zone names used by the client ("hand", "command_zone", ...).
+    pub fn from_name(name: &str) -> Option<Zone> {
+        match name {
+            "hand" => Some(Zone::Hand),
+            "battlefield" => Some(Zone::Battlefield),
+            "graveyard" => Some(Zone::Graveyard),
+            "exile" => Some(Zone::Exile),
+            "command_zone" => Some(Zone::CommandZone),
+            "library" => Some(Zone::Library),
+            _ => None,
+        }
+    }
+}
+
+/// A step of the turn, in the order they happen.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Step {
+    #[default]
+    Untap,
+    Upkeep,
+    Draw,
+    Main1,
+    BeginCombat,
+    DeclareAttackers,
+    DeclareBlockers,
+    CombatDamage,
+    EndCombat,
+    Main2,
+    End,
+    Cleanup,
+}
+
+impl Step {
+    /// The step after this one, or `None` after cleanup when the turn is over.
+    pub fn next(self) -> Option<Step> {
+        match self {
+            Step::Untap => Some(Step::Upkeep),
+            Step::Upkeep => Some(Step::Draw),
+            Step::Draw => Some(Step::Main1),
+            Step::Main1 => Some(Step::BeginCombat),
+            Step::BeginCombat => Some(Step::DeclareAttackers),
+            Step::DeclareAttackers => Some(Step::DeclareBlockers),
+            Step::DeclareBlockers => Some(Step::CombatDamage),
+            Step::CombatDamage => Some(Step::EndCombat),
+            Step::EndCombat => Some(Step::Main2),
+            Step::Main2 => Some(Step::End),
+            Step::End => Some(Step::Cleanup),
+            Step::Cleanup => None,
+        }
+    }
+
+    pub fn phase(self) -> &'static str {
+        match self {
+            Step::Untap | Step::Upkeep | Step::Draw => "Beginning",
+            Step::Main1 => "Precombat Main",
+            Step::BeginCombat
+            | Step::DeclareAttackers
+            | Step::DeclareBlockers
+            | Step::CombatDamage
+            | Step::EndCombat => "Combat",
+            Step::Main2 => "Postcombat Main",
+            Step::End | Step::Cleanup => "Ending",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Step::Untap => "Untap",
+            Step::Upkeep => "Upkeep",
+            Step::Draw => "Draw",
+            Step::Main1 | Step::Main2 => "Main",
+            Step::BeginCombat => "Beginning of Combat",
+            Step::DeclareAttackers => "Declare Attackers",
+            Step::DeclareBlockers => "Declare Blockers",
+            Step::CombatDamage => "Combat Damage",
+            Step::EndCombat => "End of Combat",
+            Step::End => "End",
+            Step::Cleanup => "Cleanup",
+        }
+    }
+}
+
+/// Steps the server can carry out for the active player.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct TurnAutomation {
+    /// Untap the active player's permanents when their untap step starts.
+    pub auto_untap: bool,
+    /// Draw a card for the active player when their draw step starts.
+    pub auto_draw: bool,
+}
+
+/// What gets pushed through a game's broadcast channel.
+#[derive(Debug, Clone)]
+pub enum Broadcast {
+    /// A state change. Each connection builds its own filtered view from the snapshot.
+    State(Arc<GameSession>),
+    /// A message that is identical for every connection.
+    Message(String),
+    /// A new log entry. Each connection redacts it for its viewer.
+    Event(GameEvent),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub id: String,
@@ -46,6 +213,29 @@ pub struct Player {
     pub is_active: bool,
     pub join_order: usize,
     pub profile_picture: String,
+    #[serde(default)]
+    pub is_eliminated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elimination_reason: Option<EliminationReason>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EliminationReason {
+    Life,
+    Poison,
+    CommanderDamage,
+    Conceded,
+}
+
+impl EliminationReason {
+    pub fn describe(self) -> &'static str {
+        match self {
+            EliminationReason::Life => "ran out of life",
+            EliminationReason::Poison => "took 10 poison counters",
+            EliminationReason::CommanderDamage => "took 21 damage from a commander",
+            EliminationReason::Conceded => "conceded",
+        }
+    }
 }
 
 impl Player {
@@ -66,6 +256,8 @@ impl Player {
             is_active: true,
             join_order,
             profile_picture: format!("/GameTableData/Players/{}/profile.jpg", name),
+            is_eliminated: false,
+            elimination_reason: None,
         }
     }
 
@@ -90,18 +282,378 @@ impl Player {
             Zone::CommandZone => &mut self.command_zone,
         }
     }
+
+    /// Clears every zone and replaces the library and command zone with a freshly loaded deck.
+    pub fn load_deck(&mut self, library: Vec<Card>, command_zone: Vec<Card>) {
+        self.hand.clear();
+        self.battlefield.clear();
+        self.graveyard.clear();
+        self.exile.clear();
+        self.library = library;
+        self.command_zone = command_zone;
+    }
+
+    /// Puts a card on the bottom of the library. The top is the end of the
+    /// vector, where draws take from.
+    pub fn put_on_library_bottom(&mut self, card: Card) {
+        self.library.insert(0, card);
+    }
+
+    /// Takes up to `count` cards off the top of the library, top card first.
+    fn take_from_library_top(&mut self, count: usize) -> Vec<Card> {
+        let at = self.library.len().saturating_sub(count);
+        let mut cards = self.library.split_off(at);
+        cards.reverse();
+        cards
+    }
+
+    /// Takes the cards with the given ids out of the library, in the order given.
+    fn take_from_library(&mut self, card_ids: &[String]) -> Vec<Card> {
+        card_ids
+            .iter()
+            .filter_map(|id| {
+                let index = self.library.iter().position(|c| &c.id == id)?;
+                Some(self.library.remove(index))
+            })
+            .collect()
+    }
+
+    /// Finishes a scry: `top_cards` go back on top with the first one as the new
+    /// top card, and `bottom_cards` go on the bottom with the first one lowest.
+    pub fn finish_scry(&mut self, top_cards: &[String], bottom_cards: &[String]) {
+        let mut top = self.take_from_library(top_cards);
+        let mut library = self.take_from_library(bottom_cards);
+        top.reverse();
+        library.append(&mut self.library);
+        library.append(&mut top);
+        self.library = library;
+    }
+
+    /// Finishes a surveil: `graveyard_cards` go to the graveyard and `top_cards`
+    /// go back on top with the first one as the new top card.
+    pub fn finish_surveil(&mut self, top_cards: &[String], graveyard_cards: &[String]) {
+        let mut graveyard = self.take_from_library(graveyard_cards);
+        self.graveyard.append(&mut graveyard);
+        let mut top = self.take_from_library(top_cards);
+        top.reverse();
+        self.library.append(&mut top);
+    }
+
+    /// Moves up to `count` cards from the top of the library to the hand.
+    /// Returns how many were drawn.
+    pub fn draw_cards(&mut self, count: usize) -> usize {
+        let drawn = self.take_from_library_top(count);
+        let count = drawn.len();
+        for mut card in drawn {
+            card.is_revealed = false;
+            self.hand.push(card);
+        }
+        count
+    }
+
+    /// Takes a chosen card out of the library and puts it into `to_zone`,
+    /// shuffling afterwards if asked. Returns the card that was moved.
+    pub fn tutor(&mut self, card_id: &str, to_zone: &Zone, shuffle: bool) -> Result<Card, String> {
+        if *to_zone == Zone::Library {
+            return Err("That card is already in your library".to_string());
+        }
+        let index = self.library.iter().position(|c| c.id == card_id).ok_or("That card isn't in your library")?;
+        let mut card = self.library.remove(index);
+        card.is_revealed = false;
+        card.is_flipped = false;
+        self.get_zone_mut(to_zone).push(card.clone());
+        if shuffle {
+            self.shuffle_library();
+        }
+        Ok(card)
+    }
+
+    /// Exiles the top `count` cards face down. Returns how many were exiled.
+    pub fn exile_top_face_down(&mut self, count: usize) -> usize {
+        let exiled = self.take_from_library_top(count);
+        let count = exiled.len();
+        for mut card in exiled {
+            card.is_revealed = false;
+            card.is_flipped = true;
+            self.exile.push(card);
+        }
+        count
+    }
+
+    /// Reveals cards from the top of the library until one named `card_name`
+    /// turns up. That card goes to `hit_zone` and the rest to `rest_zone`, where
+    /// `Zone::Library` means the bottom in a random order. Returns the revealed
+    /// cards in order and whether the last one was a hit.
+    pub fn reveal_until(&mut self, card_name: &str, hit_zone: &Zone, rest_zone: &Zone) -> Result<(Vec<Card>, bool), String> {
+        use rand::seq::SliceRandom;
+
+        let card_name = card_name.trim();
+        if card_name.is_empty() {
+            return Err("Name the card to reveal until".to_string());
+        }
+        if *hit_zone == Zone::Library {
+            return Err("The card found has to go somewhere other than the library".to_string());
+        }
+        if self.library.is_empty() {
+            return Err("Your library is empty".to_string());
+        }
+
+        // Counted from the top, which is the end of the library
+        let hit_index = self.library.iter().rev().position(|c| c.name.eq_ignore_ascii_case(card_name));
+        let count = hit_index.map_or(self.library.len(), |i| i + 1);
+        let mut revealed = self.take_from_library_top(count);
+        for card in revealed.iter_mut() {
+            card.is_revealed = false;
+            card.is_flipped = false;
+        }
+
+        let mut rest = revealed.clone();
+        if hit_index.is_some() {
+            if let Some(hit) = rest.pop() {
+                self.get_zone_mut(hit_zone).push(hit);
+            }
+        }
+        if *rest_zone == Zone::Library {
+            rest.shuffle(&mut rand::thread_rng());
+            rest.append(&mut self.library);
+            self.library = rest;
+        } else {
+            self.get_zone_mut(rest_zone).append(&mut rest);
+        }
+        Ok((revealed, hit_index.is_some()))
+    }
+
+    /// Shuffles the library and gives every card in it a fresh id, so cards
+    /// seen before the shuffle can't be tracked to their new position.
+    pub fn shuffle_library(&mut self) {
+        use rand::seq::SliceRandom;
+        let mut rng = rand::thread_rng();
+        self.library.shuffle(&mut rng);
+        for card in self.library.iter_mut() {
+            card.id = Uuid::new_v4().to_string();
+            card.is_revealed = false;
+        }
+    }
+
+    /// Returns this player as seen by `viewer_id`. Libraries are always card backs,
+    /// hands and face-down permanents are card backs for everyone but the owner.
+    /// Revealed cards stay visible.
+    pub fn view_for(&self, viewer_id: &str) -> Player {
+        let is_owner = self.id == viewer_id;
+        let hide = |card: &Card| if card.is_revealed { card.clone() } else { card.redacted() };
+
+        let mut view = self.clone();
+        view.library = self.library.iter().map(hide).collect();
+        if !is_owner {
+            view.hand = self.hand.iter().map(hide).collect();
+            view.battlefield = self.battlefield
+                .iter()
+                .map(|c| if c.is_flipped { hide(c) } else { c.clone() })
+                .collect();
+            view.exile = self.exile
+                .iter()
+                .map(|c| if c.is_flipped { hide(c) } else { c.clone() })
+                .collect();
+        }
+        view
+    }
+}
+
+/// Seats a game gets unless its host picks another number.
+pub const DEFAULT_MAX_PLAYERS: usize = 4;
+
+/// Most seats a game may have.
+pub const MAX_PLAYERS_LIMIT: usize = 8;
+
+/// How a game was set up by whoever created it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSettings {
+    /// The user who created the game, if they were logged in.
+    #[serde(default)]
+    pub host_id: Option<String>,
+    #[serde(default)]
+    pub host_name: Option<String>,
+    /// Public games are listed in the lobby; private ones are joined by id.
+    pub is_public: bool,
+    /// bcrypt hash of the join password. Never sent to clients.
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    pub max_players: usize,
+    pub format: String,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            host_id: None,
+            host_name: None,
+            is_public: true,
+            password_hash: None,
+            max_players: DEFAULT_MAX_PLAYERS,
+            format: "Commander".to_string(),
+        }
+    }
+}
+
+/// A game as shown in the lobby's game browser.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameListing {
+    pub game_id: String,
+    pub host: Option<String>,
+    pub seats_filled: usize,
+    pub max_players: usize,
+    pub format: String,
+    pub has_password: bool,
+    pub created_at: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameSession {
     pub id: String,
     pub players: HashMap<String, Player>,
+    /// Shared area for cards that belong to nobody's board, like emblems and dungeons.
+    /// Each card's `owner_id` is the player who put it there.
     pub battlefield: Vec<Card>,
+    /// Spells waiting to resolve, bottom first.
+    #[serde(default)]
+    pub stack: Vec<StackItem>,
+    /// Player ids in turn order. Each player's `join_order` is their index here.
+    #[serde(default)]
+    pub seats: Vec<String>,
+    /// Index into `seats` of the player whose turn it is.
     pub current_turn_player: usize,
     pub turn_number: u32,
+    #[serde(default)]
+    pub step: Step,
+    #[serde(default)]
+    pub automation: TurnAutomation,
+    /// Combat damage dealt by each commander, keyed by victim id then commander card id.
+    #[serde(default)]
+    pub commander_damage: HashMap<String, BTreeMap<String, i32>>,
     pub created_at: u64,
+    /// Incremented on every state change.
+    #[serde(default)]
+    pub version: u64,
+    /// History of applied actions, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<GameEvent>,
+    /// An undo waiting for other affected players to approve it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_undo: Option<PendingUndo>,
+    /// Set while players are still deciding on their opening hands.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mulligan: Option<MulliganPhase>,
+    /// How many spectators are connected. They watch without a seat.
+    #[serde(default)]
+    pub spectators: usize,
+    #[serde(default)]
+    pub settings: GameSettings,
+    /// Users who passed the join check and may take a seat when they connect.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub admitted: HashSet<String>,
+    /// Unix time of the last state change, used to expire idle games.
+    #[serde(default)]
+    pub last_activity: u64,
+    /// When players were warned that the game is about to expire.
+    #[serde(skip)]
+    pub idle_warned_at: Option<u64>,
+    /// Board snapshots taken before recent actions, oldest first.
+    #[serde(skip)]
+    pub history: Vec<HistoryEntry>,
+    /// Held while one action is described, snapshotted, applied and logged,
+    /// so actions from different connections can't interleave.
+    #[serde(skip)]
+    pub action_lock: Arc<tokio::sync::Mutex<()>>,
     #[serde(skip)]
-    pub tx: Option<broadcast::Sender<String>>,
+    pub tx: Option<broadcast::Sender<Broadcast>>,
+    /// Set whenever state changes and cleared once the session is persisted.
+    #[serde(skip)]
+    pub dirty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackItem {
+    pub card: Card,
+    /// The player who cast the spell.
+    pub controller_id: String,
+    /// Permanent spells enter the battlefield when they resolve; anything else
+    /// goes to the graveyard.
+    pub is_permanent: bool,
+}
+
+/// Poison counters that knock a player out.
+pub const POISON_LIMIT: i32 = 10;
+
+/// Damage from a single commander that knocks a player out.
+pub const COMMANDER_DAMAGE_LIMIT: i32 = 21;
+
+/// How many actions can be undone.
+pub const HISTORY_LIMIT: usize = 25;
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub actor_id: String,
+    pub summary: String,
+    /// The board as it was before the action.
+    pub before: Box<GameSession>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUndo {
+    pub requester_id: String,
+    pub count: usize,
+    pub summaries: Vec<String>,
+    /// Affected players who still have to approve.
+    pub awaiting: Vec<String>,
+    /// The game version the request was made at; any later change cancels it.
+    pub version: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UndoOutcome {
+    Applied { summaries: Vec<String> },
+    AwaitingVotes { awaiting: Vec<String> },
+    Rejected,
+}
+
+/// Cards in an opening hand.
+pub const OPENING_HAND_SIZE: usize = 7;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MulliganDecision {
+    /// Mulligans taken so far.
+    pub mulligans: usize,
+    pub kept: bool,
+}
+
+/// The opening-hand phase before the first turn. Everyone draws seven and
+/// either keeps or mulligans; London rules, so each mulligan redraws seven and
+/// the kept hand puts one card per mulligan on the bottom.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MulliganPhase {
+    /// The first mulligan costs no card, as is usual in multiplayer.
+    pub free_first: bool,
+    pub decisions: BTreeMap<String, MulliganDecision>,
+}
+
+impl MulliganPhase {
+    /// Cards a player who took `mulligans` mulligans puts on the bottom when keeping.
+    pub fn cards_to_bottom(&self, mulligans: usize) -> usize {
+        if self.free_first {
+            mulligans.saturating_sub(1)
+        } else {
+            mulligans
+        }
+    }
+
+    /// Players who haven't kept a hand yet.
+    pub fn deciding(&self) -> Vec<&str> {
+        self.decisions
+            .iter()
+            .filter(|(_, decision)| !decision.kept)
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
 }
 
 impl GameSession {
@@ -111,18 +663,256 @@ impl GameSession {
             id: game_id,
             players: HashMap::new(),
             battlefield: Vec::new(),
+            stack: Vec::new(),
+            seats: Vec::new(),
             current_turn_player: 0,
             turn_number: 1,
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            step: Step::Untap,
+            automation: TurnAutomation::default(),
+            commander_damage: HashMap::new(),
+            created_at: unix_now(),
+            version: 0,
+            events: Vec::new(),
+            pending_undo: None,
+            mulligan: None,
+            spectators: 0,
+            settings: GameSettings::default(),
+            admitted: HashSet::new(),
+            last_activity: unix_now(),
+            idle_warned_at: None,
+            history: Vec::new(),
+            action_lock: Arc::default(),
             tx: Some(tx),
+            dirty: true,
         }
     }
 
+    /// Re-creates the broadcast channel for a session loaded from storage.
+    pub fn attach_channel(&mut self) {
+        let (tx, _) = broadcast::channel(100);
+        self.tx = Some(tx);
+    }
+
+    /// Seats a new player after everyone already at the table.
     pub fn add_player(&mut self, player: Player) {
+        if !self.players.contains_key(&player.id) {
+            self.clear_history();
+        }
+        if !self.seats.contains(&player.id) {
+            self.seats.push(player.id.clone());
+        }
         self.players.insert(player.id.clone(), player);
+        self.sync_join_order();
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.players.len() >= self.settings.max_players
+    }
+
+    /// Checks whether `user_id` may take a seat. Anyone already seated can
+    /// always come back; everyone else needs a free seat and, if the game has
+    /// a password, to be its host or to have been admitted by the join check.
+    pub fn can_seat(&self, user_id: &str) -> Result<(), String> {
+        if self.players.contains_key(user_id) {
+            return Ok(());
+        }
+        if self.is_full() {
+            return Err("This game is full".to_string());
+        }
+        let is_host = self.settings.host_id.as_deref() == Some(user_id);
+        if self.settings.password_hash.is_some() && !is_host && !self.admitted.contains(user_id) {
+            return Err("This game needs a password".to_string());
+        }
+        Ok(())
+    }
+
+    /// Checks whether `user_id` may watch. Public games without a password are
+    /// open to anyone; otherwise only members may watch.
+    pub fn can_spectate(&self, user_id: &str) -> Result<(), String> {
+        if self.is_member(user_id) {
+            return Ok(());
+        }
+        if self.settings.password_hash.is_some() {
+            return Err("This game needs a password".to_string());
+        }
+        if !self.settings.is_public {
+            return Err("This game is private".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether `user_id` sits at this game, hosts it or passed its join check.
+    pub fn is_member(&self, user_id: &str) -> bool {
+        self.players.contains_key(user_id)
+            || self.admitted.contains(user_id)
+            || self.settings.host_id.as_deref() == Some(user_id)
+    }
+
+    pub fn listing(&self) -> GameListing {
+        GameListing {
+            game_id: self.id.clone(),
+            host: self.settings.host_name.clone(),
+            seats_filled: self.players.len(),
+            max_players: self.settings.max_players,
+            format: self.settings.format.clone(),
+            has_password: self.settings.password_hash.is_some(),
+            created_at: self.created_at,
+        }
+    }
+
+    /// Counts a spectator in or out and lets the table know. Spectators aren't
+    /// part of the game, so this isn't a state change: the version stays put
+    /// and undo history and pending undos are untouched.
+    pub fn set_spectating(&mut self, joined: bool) {
+        self.spectators = if joined {
+            self.spectators + 1
+        } else {
+            self.spectators.saturating_sub(1)
+        };
+        self.broadcast_message(serde_json::json!({
+            "SpectatorCount": {
+                "count": self.spectators
+            }
+        }).to_string());
+    }
+
+    /// Removes a player and their seat. If it was their turn, the next seat takes over.
+    pub fn remove_player(&mut self, player_id: &str) -> Option<Player> {
+        let mut player = self.players.remove(player_id)?;
+        self.clear_history();
+
+        // Borrowed permanents go home; the leaver's own cards leave with them
+        for mut card in std::mem::take(&mut player.battlefield) {
+            match card.owner_id.take().and_then(|id| self.players.get_mut(&id)) {
+                Some(owner) => owner.battlefield.push(card),
+                None => player.battlefield.push(card),
+            }
+        }
+        let mut gone: Vec<String> = player.battlefield.iter().map(|c| c.id.clone()).collect();
+        for other in self.players.values_mut() {
+            let (lent, keep): (Vec<Card>, Vec<Card>) = other.battlefield
+                .drain(..)
+                .partition(|c| c.owner_id.as_deref() == Some(player_id));
+            other.battlefield = keep;
+            gone.extend(lent.into_iter().map(|c| c.id));
+        }
+        for card_id in &gone {
+            self.release_attachments(card_id);
+        }
+        self.stack.retain(|item| item.controller_id != player_id);
+        self.battlefield.retain(|c| c.owner_id.as_deref() != Some(player_id));
+        self.drop_from_mulligan(player_id);
+        if let Some(seat) = self.seats.iter().position(|id| id == player_id) {
+            self.seats.remove(seat);
+            if seat < self.current_turn_player {
+                self.current_turn_player -= 1;
+            } else if seat == self.current_turn_player && !self.seats.is_empty() {
+                // The next seat has shifted into this index, unless the leaver sat last
+                if self.current_turn_player >= self.seats.len() {
+                    self.current_turn_player = 0;
+                    self.turn_number += 1;
+                }
+                if !self.takes_turns(&self.seats[self.current_turn_player]) {
+                    self.advance_seat();
+                }
+            }
+        }
+        if self.seats.is_empty() {
+            self.current_turn_player = 0;
+        }
+        self.sync_join_order();
+        self.update_active_flags();
+        Some(player)
+    }
+
+    /// Rebuilds the seat list from `join_order` for sessions saved before seats existed.
+    pub fn rebuild_seats(&mut self) {
+        let mut players: Vec<&Player> = self.players.values().collect();
+        players.sort_by(|a, b| a.join_order.cmp(&b.join_order).then_with(|| a.id.cmp(&b.id)));
+        self.seats = players.into_iter().map(|p| p.id.clone()).collect();
+        if self.current_turn_player >= self.seats.len() {
+            self.current_turn_player = 0;
+        }
+        self.sync_join_order();
+    }
+
+    fn sync_join_order(&mut self) {
+        for (seat, id) in self.seats.iter().enumerate() {
+            if let Some(player) = self.players.get_mut(id) {
+                player.join_order = seat;
+            }
+        }
+    }
+
+    /// Whether the player in a seat still gets turns.
+    fn takes_turns(&self, player_id: &str) -> bool {
+        self.players.get(player_id).is_some_and(|p| !p.is_eliminated)
+    }
+
+    /// The id of the player whose turn it is.
+    pub fn active_player_id(&self) -> Option<&str> {
+        self.seats.get(self.current_turn_player).map(|id| id.as_str())
+    }
+
+    /// Moves to the next seat that takes turns, starting a new round when passing seat 0.
+    /// Returns false if nobody can take a turn.
+    fn advance_seat(&mut self) -> bool {
+        for _ in 0..self.seats.len() {
+            self.current_turn_player = (self.current_turn_player + 1) % self.seats.len();
+            if self.current_turn_player == 0 {
+                self.turn_number += 1;
+            }
+            if self.takes_turns(&self.seats[self.current_turn_player]) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn update_active_flags(&mut self) {
+        let active_id = self.active_player_id().map(|id| id.to_string());
+        for player in self.players.values_mut() {
+            player.is_active = Some(&player.id) == active_id.as_ref();
+        }
+    }
+
+    /// Puts the seats in the given order. The same player keeps the turn.
+    pub fn reorder_seats(&mut self, order: Vec<String>) -> Result<(), String> {
+        let mut current = self.seats.clone();
+        let mut requested = order.clone();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Err("The new seat order must list every seated player exactly once".to_string());
+        }
+
+        let active_id = self.active_player_id().map(|id| id.to_string());
+        self.seats = order;
+        self.current_turn_player = active_id
+            .and_then(|id| self.seats.iter().position(|seat| *seat == id))
+            .unwrap_or(0);
+        self.sync_join_order();
+        Ok(())
+    }
+
+    /// Picks a random starting player and rotates the seats so they sit first,
+    /// keeping everyone's neighbours. Resets the turn count.
+    pub fn randomize_starting_player(&mut self) -> Option<String> {
+        use rand::Rng;
+        let candidates: Vec<usize> = (0..self.seats.len())
+            .filter(|&seat| self.takes_turns(&self.seats[seat]))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let first = candidates[rand::thread_rng().gen_range(0..candidates.len())];
+        self.seats.rotate_left(first);
+        self.current_turn_player = 0;
+        self.turn_number = 1;
+        self.step = Step::Untap;
+        self.sync_join_order();
+        self.update_active_flags();
+        self.active_player_id().map(|id| id.to_string())
     }
 
     pub fn get_player(&self, player_id: &str) -> Option<&Player> {
@@ -136,12 +926,96 @@ impl GameSession {
     pub fn update_life(&mut self, player_id: &str, life_delta: i32) -> Result<i32, String> {
         if let Some(player) = self.get_player_mut(player_id) {
             player.life += life_delta;
-            Ok(player.life)
+            let life = player.life;
+            self.check_elimination(player_id);
+            Ok(life)
         } else {
             Err(format!("Player {} not found", player_id))
         }
     }
 
+    /// Adjusts one of a player's own counters (poison, energy, experience).
+    pub fn update_counter(&mut self, player_id: &str, counter_type: &str, delta: i32) -> Result<i32, String> {
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let counter = match counter_type {
+            "poison" => &mut player.poison,
+            "energy" => &mut player.energy,
+            "experience" => &mut player.experience,
+            _ => return Err(format!("Unknown counter: {}", counter_type)),
+        };
+        *counter = (*counter + delta).max(0);
+        let value = *counter;
+        self.check_elimination(player_id);
+        Ok(value)
+    }
+
+    /// Which loss condition, if any, a player currently meets.
+    fn loss_condition(&self, player_id: &str) -> Option<EliminationReason> {
+        let player = self.players.get(player_id)?;
+        let commander_damage = self.commander_damage
+            .get(player_id)
+            .map(|damage| damage.values().any(|&d| d >= COMMANDER_DAMAGE_LIMIT))
+            .unwrap_or(false);
+
+        if player.life <= 0 {
+            Some(EliminationReason::Life)
+        } else if player.poison >= POISON_LIMIT {
+            Some(EliminationReason::Poison)
+        } else if commander_damage {
+            Some(EliminationReason::CommanderDamage)
+        } else {
+            None
+        }
+    }
+
+    /// Eliminates a player who meets a loss condition. Returns true if they were just eliminated.
+    pub fn check_elimination(&mut self, player_id: &str) -> bool {
+        if self.players.get(player_id).is_none_or(|p| p.is_eliminated) {
+            return false;
+        }
+        match self.loss_condition(player_id) {
+            Some(reason) => self.eliminate(player_id, reason).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Marks a player as out of the game and tells the table. If it was their
+    /// turn, the turn passes to the next player still in the game.
+    pub fn eliminate(&mut self, player_id: &str, reason: EliminationReason) -> Result<(), String> {
+        let player = self.players.get_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        if player.is_eliminated {
+            return Err(format!("{} is already out of the game", player.name));
+        }
+        player.is_eliminated = true;
+        player.elimination_reason = Some(reason);
+        let name = player.name.clone();
+
+        self.broadcast_message(serde_json::json!({
+            "PlayerEliminated": {
+                "player_id": player_id,
+                "player_name": name,
+                "reason": reason
+            }
+        }).to_string());
+
+        self.drop_from_mulligan(player_id);
+        if self.active_player_id() == Some(player_id) {
+            self.next_turn();
+        }
+        Ok(())
+    }
+
+    /// Undoes an elimination that was a mistake. The player takes turns again.
+    pub fn reinstate(&mut self, player_id: &str) -> Result<(), String> {
+        let player = self.players.get_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        if !player.is_eliminated {
+            return Err(format!("{} is still in the game", player.name));
+        }
+        player.is_eliminated = false;
+        player.elimination_reason = None;
+        Ok(())
+    }
+
     pub fn move_card(
         &mut self,
         player_id: &str,
@@ -161,121 +1035,1021 @@ impl GameSession {
             let from_zone_vec = player.get_zone_mut(&from_zone);
             from_zone_vec.retain(|c| c.id != card_id);
 
-            // Add to destination zone
-            let to_zone_vec = player.get_zone_mut(&to_zone);
-            to_zone_vec.push(card);
-
+            // Add to destination zone; a reveal only lasts until the card changes zones
+            let mut card = card;
+            card.is_revealed = false;
+            if from_zone == Zone::Battlefield && to_zone != Zone::Battlefield {
+                self.put_in_owner_zone(player_id, card, &to_zone);
+                self.release_attachments(card_id);
+            } else {
+                player.get_zone_mut(&to_zone).push(card);
+            }
             Ok(())
         } else {
             Err(format!("Player {} not found", player_id))
         }
     }
 
-    pub fn next_turn(&mut self) {
-        self.current_turn_player = (self.current_turn_player + 1) % self.players.len();
-        
-        // Update is_active for all players
-        for player in self.players.values_mut() {
-            player.is_active = false;
+    /// Puts a card that left `controller_id`'s battlefield into its owner's zone.
+    /// Cards whose owner has left the table leave the game with them.
+    fn put_in_owner_zone(&mut self, controller_id: &str, mut card: Card, zone: &Zone) {
+        let owner_id = card.owner_id.take().unwrap_or_else(|| controller_id.to_string());
+        card.leave_battlefield();
+        if let Some(owner) = self.players.get_mut(&owner_id) {
+            owner.get_zone_mut(zone).push(card);
         }
-        
-        // Set the current turn player as active
-        let player_ids: Vec<String> = self.players.keys().cloned().collect();
-        if let Some(active_id) = player_ids.get(self.current_turn_player) {
-            if let Some(player) = self.players.get_mut(active_id) {
-                player.is_active = true;
-            }
+    }
+
+    /// Puts a card from one of the player's zones into its owner's library,
+    /// `position` cards from the top (1 is the top) or from the bottom.
+    pub fn put_in_library(
+        &mut self,
+        player_id: &str,
+        card_id: &str,
+        from_zone: Zone,
+        position: usize,
+        from_bottom: bool,
+    ) -> Result<(), String> {
+        if position == 0 {
+            return Err("Library positions start at 1".to_string());
         }
-        
-        // Only increment turn number after the last player completes their turn
-        if self.current_turn_player == 0 {
-            self.turn_number += 1;
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let zone = player.get_zone_mut(&from_zone);
+        let index = zone.iter().position(|c| c.id == card_id).ok_or("Card not found")?;
+        let mut card = zone.remove(index);
+        card.is_revealed = false;
+        card.is_flipped = false;
+
+        let owner_id = match from_zone {
+            Zone::Battlefield => card.owner_id.take().unwrap_or_else(|| player_id.to_string()),
+            _ => player_id.to_string(),
+        };
+        card.leave_battlefield();
+        // Tokens that leave the battlefield cease to exist
+        let owner = self.players.get_mut(&owner_id).filter(|_| !card.is_token);
+        if let Some(owner) = owner {
+            // The top of the library is its end
+            let len = owner.library.len();
+            let index = if from_bottom {
+                (position - 1).min(len)
+            } else {
+                len.saturating_sub(position - 1)
+            };
+            owner.library.insert(index, card);
         }
+        if from_zone == Zone::Battlefield {
+            self.release_attachments(card_id);
+        }
+        Ok(())
     }
 
-    pub fn restart_game(&mut self) {
-        self.turn_number = 1;
-        self.current_turn_player = 0;
-        
-        for player in self.players.values_mut() {
-            player.life = 40;
-            player.poison = 0;
-            player.energy = 0;
-            player.experience = 0;
-            player.is_active = false;
-            
-            // Move all cards back to library
-            player.library.append(&mut player.hand);
-            player.library.append(&mut player.battlefield);
-            player.library.append(&mut player.graveyard);
-            player.library.append(&mut player.exile);
-            player.library.append(&mut player.command_zone);
+    /// Moves a permanent to `controller_id`'s battlefield, remembering who owns it.
+    /// The current controller, the owner or the new controller may do this, so
+    /// handing a card back to its owner is the same call.
+    pub fn gain_control(&mut self, actor_id: &str, card_id: &str, controller_id: &str) -> Result<(), String> {
+        let (current_id, card) = self.find_on_battlefield(card_id).ok_or("That card isn't on the battlefield")?;
+        let current_id = current_id.to_string();
+        let owner_id = card.owner_id.clone().unwrap_or_else(|| current_id.clone());
+
+        if !self.players.contains_key(controller_id) {
+            return Err(format!("Player {} not found", controller_id));
         }
-        
-        // Set first player as active
-        if let Some(first_player_id) = self.players.keys().next().cloned() {
-            if let Some(player) = self.players.get_mut(&first_player_id) {
-                player.is_active = true;
-            }
+        if ![current_id.as_str(), owner_id.as_str(), controller_id].contains(&actor_id) {
+            return Err("Only the card's owner, its controller or the player gaining it can change control".to_string());
+        }
+        if current_id == controller_id {
+            return Err("That player already controls this card".to_string());
         }
+
+        let current = self.players.get_mut(&current_id).ok_or("Card not found")?;
+        let index = current.battlefield.iter().position(|c| c.id == card_id).ok_or("Card not found")?;
+        let mut card = current.battlefield.remove(index);
+        card.owner_id = (owner_id != controller_id).then_some(owner_id);
+        if let Some(controller) = self.players.get_mut(controller_id) {
+            controller.battlefield.push(card);
+        }
+        Ok(())
     }
 
-    pub fn undo_turn(&mut self) {
-        if self.current_turn_player == 0 {
-            // If we're at player 0, we need to go back to the last player of the previous turn
-            if self.turn_number > 1 {
-                self.current_turn_player = self.players.len() - 1;
-                self.turn_number -= 1;
+    /// Returns a borrowed permanent to its owner's battlefield.
+    pub fn return_control(&mut self, actor_id: &str, card_id: &str) -> Result<(), String> {
+        let (_, card) = self.find_on_battlefield(card_id).ok_or("That card isn't on the battlefield")?;
+        let owner_id = card.owner_id.clone().ok_or("Its owner already controls that card")?;
+        self.gain_control(actor_id, card_id, &owner_id)
+    }
+
+    /// Gives every permanent controlled by someone other than its owner back to the owner.
+    fn return_borrowed_cards(&mut self) {
+        let mut borrowed = Vec::new();
+        for player in self.players.values_mut() {
+            let (keep, lent): (Vec<Card>, Vec<Card>) = player.battlefield.drain(..).partition(|c| c.owner_id.is_none());
+            player.battlefield = keep;
+            borrowed.extend(lent);
+        }
+        for mut card in borrowed {
+            if let Some(owner) = card.owner_id.take().and_then(|id| self.players.get_mut(&id)) {
+                owner.battlefield.push(card);
+            }
+        }
+    }
+
+    fn find_on_battlefield(&self, card_id: &str) -> Option<(&str, &Card)> {
+        self.players.values().find_map(|player| {
+            player.battlefield
+                .iter()
+                .find(|c| c.id == card_id)
+                .map(|card| (player.id.as_str(), card))
+        })
+    }
+
+    /// Attaches one of a player's permanents to a card on any battlefield.
+    pub fn attach_card(&mut self, player_id: &str, card_id: &str, host_id: &str, is_aura: bool) -> Result<(), String> {
+        if card_id == host_id {
+            return Err("A card can't be attached to itself".to_string());
+        }
+        let (host_owner, host) = self.find_on_battlefield(host_id).ok_or("The host must be on the battlefield")?;
+        let host_position = (host_owner == player_id).then_some((host.position_x, host.position_y));
+
+        // Refuse cycles such as attaching an equipment to the aura that's on it
+        let mut next = host.attached_to.clone();
+        while let Some(id) = next {
+            if id == card_id {
+                return Err("Those cards are already attached the other way round".to_string());
+            }
+            next = self.find_on_battlefield(&id).and_then(|(_, c)| c.attached_to.clone());
+        }
+
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let card = player.battlefield
+            .iter_mut()
+            .find(|c| c.id == card_id)
+            .ok_or("Only cards on the battlefield can be attached")?;
+        card.attached_to = Some(host_id.to_string());
+        card.is_aura = is_aura;
+        // Tuck the attachment under its host when they share a battlefield
+        if let Some((x, y)) = host_position {
+            card.position_x = x + ATTACHMENT_OFFSET.0;
+            card.position_y = y + ATTACHMENT_OFFSET.1;
+        }
+        Ok(())
+    }
+
+    pub fn detach_card(&mut self, player_id: &str, card_id: &str) -> Result<(), String> {
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let card = player.battlefield
+            .iter_mut()
+            .find(|c| c.id == card_id && c.attached_to.is_some())
+            .ok_or("That card isn't attached to anything")?;
+        card.attached_to = None;
+        card.is_aura = false;
+        Ok(())
+    }
+
+    /// Deals with everything attached to a card that just left the battlefield:
+    /// auras go to their owner's graveyard (tokens cease to exist), anything else
+    /// falls off and stays where it is.
+    pub fn release_attachments(&mut self, host_id: &str) {
+        let mut hosts = vec![host_id.to_string()];
+        while let Some(host_id) = hosts.pop() {
+            let mut fallen = Vec::new();
+            for player in self.players.values_mut() {
+                let mut index = 0;
+                while index < player.battlefield.len() {
+                    let card = &mut player.battlefield[index];
+                    if card.attached_to.as_deref() != Some(host_id.as_str()) {
+                        index += 1;
+                        continue;
+                    }
+                    if card.is_aura {
+                        let aura = player.battlefield.remove(index);
+                        hosts.push(aura.id.clone());
+                        if !aura.is_token {
+                            fallen.push((player.id.clone(), aura));
+                        }
+                    } else {
+                        card.attached_to = None;
+                        index += 1;
+                    }
+                }
+            }
+            for (controller_id, aura) in fallen {
+                self.put_in_owner_zone(&controller_id, aura, &Zone::Graveyard);
+            }
+        }
+    }
+
+    /// Moves a permanent on its battlefield, taking everything attached to it along.
+    pub fn move_on_battlefield(&mut self, player_id: &str, card_id: &str, x: f32, y: f32) -> Result<(), String> {
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let card = player.battlefield
+            .iter_mut()
+            .find(|c| c.id == card_id)
+            .ok_or("Card not found")?;
+        let (dx, dy) = (x - card.position_x, y - card.position_y);
+        card.position_x = x;
+        card.position_y = y;
+
+        let mut hosts = vec![card_id.to_string()];
+        while let Some(host_id) = hosts.pop() {
+            for card in player.battlefield.iter_mut().filter(|c| c.attached_to.as_deref() == Some(host_id.as_str())) {
+                card.position_x += dx;
+                card.position_y += dy;
+                hosts.push(card.id.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts a card from one of the player's zones on top of the stack.
+    pub fn cast_spell(&mut self, player_id: &str, card_id: &str, from_zone: Zone, is_permanent: bool) -> Result<(), String> {
+        if from_zone == Zone::Battlefield {
+            return Err("Cards on the battlefield can't be cast".to_string());
+        }
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let zone = player.get_zone_mut(&from_zone);
+        let index = zone.iter().position(|c| c.id == card_id).ok_or("Card not found")?;
+        let mut card = zone.remove(index);
+        card.is_revealed = false;
+
+        self.stack.push(StackItem {
+            card,
+            controller_id: player_id.to_string(),
+            is_permanent,
+        });
+        Ok(())
+    }
+
+    /// Resolves the top of the stack. Permanents enter the battlefield under their
+    /// caster's control, everything else goes to its owner's graveyard.
+    pub fn resolve_stack(&mut self) -> Result<StackItem, String> {
+        let item = self.stack.pop().ok_or("The stack is empty")?;
+        let mut card = item.card.clone();
+
+        // Face-down permanent spells stay face down on the battlefield
+        if item.is_permanent {
+            let owner_id = card.owner_id.clone().unwrap_or_else(|| item.controller_id.clone());
+            card.owner_id = (owner_id != item.controller_id).then_some(owner_id);
+            if let Some(controller) = self.players.get_mut(&item.controller_id) {
+                controller.battlefield.push(card);
             }
         } else {
-            // Otherwise just go to the previous player
-            self.current_turn_player -= 1;
+            card.is_flipped = false;
+            self.put_in_owner_zone(&item.controller_id, card, &Zone::Graveyard);
+        }
+        Ok(item)
+    }
+
+    /// Removes a spell from anywhere on the stack and puts it into its owner's graveyard.
+    pub fn counter_spell(&mut self, card_id: &str) -> Result<StackItem, String> {
+        let index = self.stack
+            .iter()
+            .position(|item| item.card.id == card_id)
+            .ok_or("That spell isn't on the stack")?;
+        let item = self.stack.remove(index);
+        let mut card = item.card.clone();
+        card.is_flipped = false;
+        self.put_in_owner_zone(&item.controller_id, card, &Zone::Graveyard);
+        Ok(item)
+    }
+
+    /// Puts the stack in the given order, bottom first.
+    pub fn reorder_stack(&mut self, order: &[String]) -> Result<(), String> {
+        let mut current: Vec<&str> = self.stack.iter().map(|item| item.card.id.as_str()).collect();
+        let mut requested: Vec<&str> = order.iter().map(|id| id.as_str()).collect();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Err("The new stack order must list every spell on the stack exactly once".to_string());
+        }
+
+        let mut items = std::mem::take(&mut self.stack);
+        for id in order {
+            if let Some(index) = items.iter().position(|item| &item.card.id == id) {
+                self.stack.push(items.remove(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// The printing of a card on the player's battlefield, used to look up its tokens.
+    pub fn battlefield_printing(&self, player_id: &str, card_id: &str) -> Result<(String, String), String> {
+        let player = self.get_player(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let card = player.battlefield
+            .iter()
+            .find(|c| c.id == card_id)
+            .ok_or("Tokens can only be made from cards on the battlefield")?;
+        match (&card.set_code, &card.collector_number) {
+            (Some(set_code), Some(collector_number)) => Ok((set_code.clone(), collector_number.clone())),
+            _ => Err("That card has no printing to look up tokens for".to_string()),
+        }
+    }
+
+    /// Puts `count` copies of a token onto the player's battlefield, fanned out
+    /// from the card that made them. Returns the new card ids.
+    pub fn create_tokens(&mut self, player_id: &str, source_id: &str, token: &RelatedToken, count: usize) -> Result<Vec<String>, String> {
+        if count == 0 || count > MAX_TOKENS_PER_REQUEST {
+            return Err(format!("You can create 1 to {} tokens at a time", MAX_TOKENS_PER_REQUEST));
+        }
+        let player = self.get_player_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let source = player.battlefield
+            .iter()
+            .find(|c| c.id == source_id)
+            .ok_or("Tokens can only be made from cards on the battlefield")?;
+        let (x, y) = (source.position_x, source.position_y);
+
+        let mut ids = Vec::with_capacity(count);
+        for i in 1..=count {
+            let step = i as f32;
+            let card = token.to_card(x + TOKEN_OFFSET.0 * step, y + TOKEN_OFFSET.1 * step);
+            ids.push(card.id.clone());
+            player.battlefield.push(card);
+        }
+        Ok(ids)
+    }
+
+    /// Puts a card such as an emblem or dungeon in the shared area.
+    pub fn add_shared_card(&mut self, owner_id: &str, mut card: Card) {
+        card.owner_id = Some(owner_id.to_string());
+        self.battlefield.push(card);
+    }
+
+    pub fn move_shared_card(&mut self, card_id: &str, x: f32, y: f32) -> Result<(), String> {
+        let card = self.battlefield
+            .iter_mut()
+            .find(|c| c.id == card_id)
+            .ok_or("Card not found in the shared area")?;
+        card.position_x = x;
+        card.position_y = y;
+        Ok(())
+    }
+
+    pub fn remove_shared_card(&mut self, card_id: &str) -> Result<Card, String> {
+        let index = self.battlefield
+            .iter()
+            .position(|c| c.id == card_id)
+            .ok_or("Card not found in the shared area")?;
+        Ok(self.battlefield.remove(index))
+    }
+
+    /// Adds `delta` counters of `counter_type` to a permanent or a card in the
+    /// shared area (a dungeon's current room, say). Counters can't go
+    /// below zero and are removed once none are left. Returns the new count.
+    pub fn update_card_counter(
+        &mut self,
+        player_id: &str,
+        card_id: &str,
+        counter_type: &str,
+        delta: i32,
+    ) -> Result<i32, String> {
+        let counter_type = counter_type.trim();
+        if counter_type.is_empty() || counter_type.len() > MAX_COUNTER_NAME_LEN {
+            return Err(format!("Counter names must be 1 to {} characters", MAX_COUNTER_NAME_LEN));
+        }
+
+        let player = self.players.get_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let card = player.battlefield
+            .iter_mut()
+            .chain(self.battlefield.iter_mut())
+            .find(|c| c.id == card_id)
+            .ok_or("Counters can only be put on cards on the battlefield")?;
+
+        let count = (card.counters.get(counter_type).copied().unwrap_or(0) + delta).max(0);
+        if count == 0 {
+            card.counters.remove(counter_type);
+        } else {
+            card.counters.insert(counter_type.to_string(), count);
+        }
+        Ok(count)
+    }
+
+    /// Finds a commander card anywhere on the table.
+    pub fn find_commander(&self, card_id: &str) -> Option<&Card> {
+        self.players.values().find_map(|player| {
+            [Zone::CommandZone, Zone::Battlefield, Zone::Graveyard, Zone::Exile, Zone::Hand, Zone::Library]
+                .iter()
+                .find_map(|zone| player.get_zone(zone).iter().find(|c| c.id == card_id && c.is_commander))
+        })
+    }
+
+    /// Records `delta` damage from a commander to a player. Commander damage is
+    /// still damage, so it also comes off the victim's life total. A player who
+    /// has taken 21 or more from a single commander is eliminated.
+    /// Returns the total damage from that commander.
+    pub fn update_commander_damage(&mut self, victim_id: &str, commander_id: &str, delta: i32) -> Result<i32, String> {
+        if !self.players.contains_key(victim_id) {
+            return Err(format!("Player {} not found", victim_id));
+        }
+        if self.find_commander(commander_id).is_none() {
+            return Err("That card isn't a commander".to_string());
+        }
+
+        let damage_taken = self.commander_damage.entry(victim_id.to_string()).or_default();
+        let previous = damage_taken.get(commander_id).copied().unwrap_or(0);
+        let total = (previous + delta).max(0);
+        if total == 0 {
+            damage_taken.remove(commander_id);
+        } else {
+            damage_taken.insert(commander_id.to_string(), total);
+        }
+        if damage_taken.is_empty() {
+            self.commander_damage.remove(victim_id);
+        }
+
+        if let Some(victim) = self.players.get_mut(victim_id) {
+            victim.life -= total - previous;
+        }
+        self.check_elimination(victim_id);
+        Ok(total)
+    }
+
+    pub fn next_turn(&mut self) {
+        if self.advance_seat() {
+            self.update_active_flags();
+            self.enter_step(Step::Untap);
+        }
+    }
+
+    /// Moves to the next step, passing the turn after cleanup.
+    pub fn advance_step(&mut self) {
+        match self.step.next() {
+            Some(step) => self.enter_step(step),
+            None => self.next_turn(),
+        }
+    }
+
+    /// Makes `step` current and runs any automation turned on for it.
+    fn enter_step(&mut self, step: Step) {
+        self.step = step;
+        let automation = self.automation;
+        let Some(active_id) = self.active_player_id().map(|id| id.to_string()) else {
+            return;
+        };
+        let Some(player) = self.players.get_mut(&active_id) else {
+            return;
+        };
+        match step {
+            Step::Untap if automation.auto_untap => {
+                for card in player.battlefield.iter_mut() {
+                    card.is_tapped = false;
+                }
+            }
+            Step::Draw if automation.auto_draw => {
+                player.draw_cards(1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Serializes the `StepChanged` message announcing the current step.
+    pub fn step_message(&self) -> String {
+        serde_json::json!({
+            "StepChanged": {
+                "step": self.step,
+                "phase": self.step.phase(),
+                "label": self.step.label(),
+                "active_player_id": self.active_player_id(),
+                "turn_number": self.turn_number
+            }
+        }).to_string()
+    }
+
+    /// Starts the opening-hand phase: everyone still in the game shuffles their
+    /// hand into their library and draws seven. Only allowed before the game
+    /// gets going, since the redeal can't be undone.
+    pub fn start_mulligans(&mut self, free_first: bool) -> Result<(), String> {
+        if self.mulligan.is_some() {
+            return Err("Players are already deciding on their opening hands".to_string());
+        }
+        if self.turn_number > 1 {
+            return Err("Opening hands can only be dealt on the first turn".to_string());
+        }
+        self.clear_history();
+        let player_ids: Vec<String> = self.seats.iter().filter(|id| self.takes_turns(id)).cloned().collect();
+        for id in &player_ids {
+            self.deal_opening_hand(id);
+        }
+        self.mulligan = Some(MulliganPhase {
+            free_first,
+            decisions: player_ids.into_iter().map(|id| (id, MulliganDecision::default())).collect(),
+        });
+        Ok(())
+    }
+
+    fn deal_opening_hand(&mut self, player_id: &str) {
+        if let Some(player) = self.players.get_mut(player_id) {
+            let mut hand = std::mem::take(&mut player.hand);
+            player.library.append(&mut hand);
+            player.shuffle_library();
+            player.draw_cards(OPENING_HAND_SIZE);
+        }
+    }
+
+    /// Shuffles the player's hand away and draws a fresh seven.
+    /// Returns how many mulligans they have taken.
+    pub fn take_mulligan(&mut self, player_id: &str) -> Result<usize, String> {
+        let phase = self.mulligan.as_mut().ok_or("Nobody is deciding on an opening hand")?;
+        let decision = phase.decisions.get(player_id).ok_or("You aren't part of this mulligan")?;
+        if decision.kept {
+            return Err("You already kept your hand".to_string());
+        }
+        let mulligans = decision.mulligans + 1;
+        if phase.cards_to_bottom(mulligans) >= OPENING_HAND_SIZE {
+            return Err("You can't mulligan any further".to_string());
+        }
+        if let Some(decision) = phase.decisions.get_mut(player_id) {
+            decision.mulligans = mulligans;
+        }
+
+        self.deal_opening_hand(player_id);
+        Ok(mulligans)
+    }
+
+    /// Keeps the current hand, putting `bottom_cards` on the bottom of the library
+    /// in the order given. Ends the phase once everyone has kept.
+    pub fn keep_hand(&mut self, player_id: &str, bottom_cards: &[String]) -> Result<(), String> {
+        let phase = self.mulligan.as_ref().ok_or("Nobody is deciding on an opening hand")?;
+        let decision = phase.decisions.get(player_id).ok_or("You aren't part of this mulligan")?;
+        if decision.kept {
+            return Err("You already kept your hand".to_string());
+        }
+        let to_bottom = phase.cards_to_bottom(decision.mulligans);
+        if bottom_cards.len() != to_bottom {
+            return Err(format!("Put exactly {} card{} on the bottom", to_bottom, if to_bottom == 1 { "" } else { "s" }));
+        }
+
+        let player = self.players.get_mut(player_id).ok_or(format!("Player {} not found", player_id))?;
+        let mut indices = Vec::with_capacity(bottom_cards.len());
+        for id in bottom_cards {
+            let index = player.hand.iter().position(|c| &c.id == id).ok_or("Those cards aren't all in your hand")?;
+            if indices.contains(&index) {
+                return Err("Each card can only be put on the bottom once".to_string());
+            }
+            indices.push(index);
+        }
+        for id in bottom_cards {
+            if let Some(index) = player.hand.iter().position(|c| &c.id == id) {
+                let card = player.hand.remove(index);
+                player.put_on_library_bottom(card);
+            }
+        }
+
+        if let Some(decision) = self.mulligan.as_mut().and_then(|phase| phase.decisions.get_mut(player_id)) {
+            decision.kept = true;
+        }
+        self.finish_mulligans_if_done();
+        Ok(())
+    }
+
+    /// Stops waiting on a player who left or was eliminated mid-mulligan.
+    fn drop_from_mulligan(&mut self, player_id: &str) {
+        let removed = self.mulligan
+            .as_mut()
+            .is_some_and(|phase| phase.decisions.remove(player_id).is_some());
+        if removed {
+            self.finish_mulligans_if_done();
+            self.broadcast_message(self.mulligan_message());
+        }
+    }
+
+    fn finish_mulligans_if_done(&mut self) {
+        if self.mulligan.as_ref().is_some_and(|phase| phase.deciding().is_empty()) {
+            self.mulligan = None;
+        }
+    }
+
+    /// Builds the `MulliganStatus` broadcast telling the table who is still deciding.
+    pub fn mulligan_message(&self) -> String {
+        let deciding = self.mulligan.as_ref().map(|phase| phase.deciding()).unwrap_or_default();
+        serde_json::json!({
+            "MulliganStatus": {
+                "deciding": deciding,
+                "decisions": self.mulligan.as_ref().map(|phase| &phase.decisions),
+                "finished": self.mulligan.is_none()
+            }
+        }).to_string()
+    }
+
+    pub fn restart_game(&mut self) {
+        self.clear_history();
+        self.turn_number = 1;
+        self.mulligan = None;
+        self.current_turn_player = 0;
+        self.step = Step::Untap;
+        self.commander_damage.clear();
+        self.return_borrowed_cards();
+        self.battlefield.clear();
+        for item in std::mem::take(&mut self.stack) {
+            if let Some(player) = self.players.get_mut(&item.controller_id) {
+                player.library.push(item.card);
+            }
         }
         
-        // Update is_active for all players
         for player in self.players.values_mut() {
+            player.life = 40;
+            player.is_eliminated = false;
+            player.elimination_reason = None;
+            player.poison = 0;
+            player.energy = 0;
+            player.experience = 0;
             player.is_active = false;
+            
+            // Move all cards back to library
+            for card in player.battlefield.iter_mut() {
+                card.leave_battlefield();
+            }
+            player.library.append(&mut player.hand);
+            player.library.append(&mut player.battlefield);
+            player.library.append(&mut player.graveyard);
+            player.library.append(&mut player.exile);
+            player.library.append(&mut player.command_zone);
         }
         
-        // Set the current turn player as active
-        let player_ids: Vec<String> = self.players.keys().cloned().collect();
-        if let Some(active_id) = player_ids.get(self.current_turn_player) {
-            if let Some(player) = self.players.get_mut(active_id) {
-                player.is_active = true;
+        // The first seat that still takes turns starts
+        if let Some(first) = self.seats.iter().position(|id| self.takes_turns(id)) {
+            self.current_turn_player = first;
+        }
+        self.update_active_flags();
+    }
+
+    pub fn undo_turn(&mut self) {
+        let previous = (1..=self.seats.len())
+            .map(|step| (self.current_turn_player + self.seats.len() - step) % self.seats.len())
+            .find(|&seat| self.takes_turns(&self.seats[seat]));
+
+        if let Some(seat) = previous {
+            // Going back past seat 0 returns to the previous round
+            if seat >= self.current_turn_player {
+                if self.turn_number <= 1 {
+                    return;
+                }
+                self.turn_number -= 1;
+            }
+            self.current_turn_player = seat;
+            self.step = Step::Untap;
+            self.update_active_flags();
+        }
+    }
+
+    /// Builds the copy of this session that `viewer_id` is allowed to see.
+    /// Spectators pass [`SPECTATOR_VIEWER`], which matches no player.
+    pub fn view_for(&self, viewer_id: &str) -> GameSession {
+        let mut view = self.board_clone_with(
+            self.players
+                .iter()
+                .map(|(id, player)| (id.clone(), player.view_for(viewer_id)))
+                .collect(),
+        );
+        // Spells cast face down are only known to whoever cast them
+        for item in view.stack.iter_mut() {
+            if item.card.is_flipped && item.controller_id != viewer_id && !item.card.is_revealed {
+                item.card = item.card.redacted();
+            }
+        }
+        view.settings.password_hash = None;
+        view.admitted.clear();
+        view
+    }
+
+    /// Clones the table itself, leaving out the log, undo history and channel.
+    pub fn board_clone(&self) -> GameSession {
+        self.board_clone_with(self.players.clone())
+    }
+
+    fn board_clone_with(&self, players: HashMap<String, Player>) -> GameSession {
+        GameSession {
+            id: self.id.clone(),
+            players,
+            battlefield: self.battlefield.clone(),
+            stack: self.stack.clone(),
+            seats: self.seats.clone(),
+            current_turn_player: self.current_turn_player,
+            turn_number: self.turn_number,
+            step: self.step,
+            automation: self.automation,
+            commander_damage: self.commander_damage.clone(),
+            created_at: self.created_at,
+            version: self.version,
+            events: Vec::new(),
+            pending_undo: self.pending_undo.clone(),
+            mulligan: self.mulligan.clone(),
+            spectators: self.spectators,
+            settings: self.settings.clone(),
+            admitted: self.admitted.clone(),
+            last_activity: self.last_activity,
+            idle_warned_at: None,
+            history: Vec::new(),
+            action_lock: Arc::clone(&self.action_lock),
+            tx: None,
+            dirty: false,
+        }
+    }
+
+    /// Remembers the board as it was before an action so it can be undone.
+    pub fn push_history(&mut self, actor_id: &str, summary: String, before: GameSession) {
+        self.history.push(HistoryEntry {
+            actor_id: actor_id.to_string(),
+            summary,
+            before: Box::new(before),
+        });
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+
+    /// Forgets every undo snapshot. Used when the table itself changes, since
+    /// restoring an older board would drop or bring back whole players or
+    /// reach back past the start of the game.
+    fn clear_history(&mut self) {
+        self.history.clear();
+        self.pending_undo = None;
+    }
+
+    /// Players whose board would change if the last `count` actions were undone.
+    /// Turn changes affect everyone at the table.
+    fn undo_affected_players(&self, count: usize) -> Vec<String> {
+        let before = &self.history[self.history.len() - count].before;
+
+        if before.current_turn_player != self.current_turn_player || before.turn_number != self.turn_number {
+            return self.players.keys().cloned().collect();
+        }
+
+        self.players
+            .iter()
+            .filter(|(id, player)| {
+                let old = before.players.get(*id).map(serde_json::to_value);
+                old.and_then(|v| v.ok()) != serde_json::to_value(player).ok()
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Rolls the board back to before the last `count` actions.
+    fn apply_undo(&mut self, count: usize) -> Vec<String> {
+        let mut undone = self.history.split_off(self.history.len() - count);
+        let summaries = undone.iter().map(|e| e.summary.clone()).collect();
+
+        let mut restored = *undone.remove(0).before;
+        restored.version = self.version;
+        restored.events = std::mem::take(&mut self.events);
+        restored.history = std::mem::take(&mut self.history);
+        restored.tx = self.tx.take();
+        restored.pending_undo = None;
+        // Spectators and who may join aren't part of the game, so undo leaves them alone
+        restored.spectators = self.spectators;
+        restored.settings = std::mem::take(&mut self.settings);
+        restored.admitted = std::mem::take(&mut self.admitted);
+        *self = restored;
+
+        self.broadcast_state();
+        summaries
+    }
+
+    /// Undoes the last `count` actions right away if they only touched the
+    /// requester's own board, otherwise asks the other affected players to approve.
+    pub fn request_undo(&mut self, requester_id: &str, count: usize) -> Result<UndoOutcome, String> {
+        if count == 0 || count > self.history.len() {
+            return Err(format!("Only {} actions can be undone", self.history.len()));
+        }
+        if self.pending_undo.is_some() {
+            return Err("Another undo is already waiting for approval".to_string());
+        }
+
+        let awaiting: Vec<String> = self.undo_affected_players(count)
+            .into_iter()
+            .filter(|id| id != requester_id)
+            .collect();
+
+        if awaiting.is_empty() {
+            return Ok(UndoOutcome::Applied { summaries: self.apply_undo(count) });
+        }
+
+        let summaries = self.history[self.history.len() - count..]
+            .iter()
+            .map(|e| e.summary.clone())
+            .collect();
+        self.pending_undo = Some(PendingUndo {
+            requester_id: requester_id.to_string(),
+            count,
+            summaries,
+            awaiting: awaiting.clone(),
+            version: self.version,
+        });
+        Ok(UndoOutcome::AwaitingVotes { awaiting })
+    }
+
+    /// Records an affected player's answer to the pending undo.
+    pub fn vote_undo(&mut self, voter_id: &str, approve: bool) -> Result<UndoOutcome, String> {
+        let pending = self.pending_undo.as_mut().ok_or("There is no undo to vote on")?;
+
+        if !pending.awaiting.iter().any(|id| id == voter_id) {
+            return Err("Your approval isn't needed for this undo".to_string());
+        }
+
+        if !approve {
+            self.pending_undo = None;
+            return Ok(UndoOutcome::Rejected);
+        }
+
+        pending.awaiting.retain(|id| id != voter_id);
+        if !pending.awaiting.is_empty() {
+            return Ok(UndoOutcome::AwaitingVotes { awaiting: pending.awaiting.clone() });
+        }
+
+        let count = pending.count;
+        Ok(UndoOutcome::Applied { summaries: self.apply_undo(count) })
+    }
+
+    /// Drops a pending undo if the game has moved on since it was requested.
+    pub fn expire_pending_undo(&mut self) -> bool {
+        match &self.pending_undo {
+            Some(pending) if pending.version != self.version => {
+                self.pending_undo = None;
+                true
             }
+            _ => false,
         }
     }
 
-    pub fn broadcast_state(&self) {
+    /// Appends an entry to the game log and sends it to every connection.
+    pub fn record_event(&mut self, actor_id: &str, draft: EventDraft) {
+        let event = GameEvent {
+            seq: self.events.last().map(|e| e.seq + 1).unwrap_or(1),
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            actor_id: actor_id.to_string(),
+            kind: draft.kind,
+            summary: draft.summary,
+            private_summary: draft.private_summary,
+        };
+
+        self.events.push(event.clone());
+        if self.events.len() > EVENT_LOG_LIMIT {
+            let overflow = self.events.len() - EVENT_LOG_LIMIT;
+            self.events.drain(..overflow);
+        }
+        self.dirty = true;
+
         if let Some(tx) = &self.tx {
-            let state_json = serde_json::to_string(&self).unwrap_or_default();
-            let msg = serde_json::json!({
-                "GameState": {
-                    "state": state_json
-                }
-            }).to_string();
-            let _ = tx.send(msg);
+            let _ = tx.send(Broadcast::Event(event));
+        }
+    }
+
+    /// Returns the log entries after `since_seq`, redacted for `viewer_id`.
+    pub fn events_for(&self, viewer_id: &str, since_seq: u64) -> Vec<GameEvent> {
+        self.events
+            .iter()
+            .filter(|e| e.seq > since_seq)
+            .map(|e| e.view_for(viewer_id))
+            .collect()
+    }
+
+    pub fn broadcast_state(&mut self) {
+        self.dirty = true;
+        self.version += 1;
+        self.last_activity = unix_now();
+        self.idle_warned_at = None;
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Broadcast::State(Arc::new(self.board_clone())));
+        }
+    }
+
+    pub fn broadcast_message(&self, msg: String) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Broadcast::Message(msg));
         }
     }
 }
 
+/// Viewer id used for spectators. It matches no player, so every hand and
+/// face-down card stays hidden.
+pub const SPECTATOR_VIEWER: &str = "";
+
+/// Characters used in game ids. Leaves out 0/O, 1/I/L and U/V so codes
+/// can be read aloud and typed without mixing them up.
+const GAME_ID_ALPHABET: &[u8] = b"23456789ABCDEFGHJKMNPQRSTWXYZ";
+
+/// Game id length used unless `GAME_ID_LENGTH` says otherwise.
+pub const DEFAULT_GAME_ID_LENGTH: usize = 6;
+
+/// Shortest and longest game ids that may be configured.
+pub const MIN_GAME_ID_LENGTH: usize = 4;
+pub const MAX_GAME_ID_LENGTH: usize = 16;
+
+/// Random ids tried at one length before moving on to longer ones.
+const ID_ATTEMPTS_PER_LENGTH: usize = 20;
+
 pub struct GameManager {
     games: HashMap<String, GameSession>,
+    /// Games deleted since the last save, so they can be removed from storage.
+    deleted: Vec<String>,
+    /// Ids found in storage at startup, including saved games that failed to
+    /// load. New games never take them so they can't overwrite a stored row.
+    reserved_ids: HashSet<String>,
+    id_length: usize,
 }
 
 impl GameManager {
     pub fn new() -> Self {
         Self {
             games: HashMap::new(),
+            deleted: Vec::new(),
+            reserved_ids: HashSet::new(),
+            id_length: DEFAULT_GAME_ID_LENGTH,
+        }
+    }
+
+    /// Sets how long new game ids are, clamped to the allowed range.
+    pub fn with_id_length(mut self, length: usize) -> Self {
+        self.id_length = length.clamp(MIN_GAME_ID_LENGTH, MAX_GAME_ID_LENGTH);
+        self
+    }
+
+    /// Marks ids already used in storage so new games don't reuse them.
+    pub fn reserve_ids(&mut self, ids: impl IntoIterator<Item = String>) {
+        self.reserved_ids.extend(ids);
+    }
+
+    fn is_id_taken(&self, id: &str) -> bool {
+        self.games.contains_key(id)
+            || self.reserved_ids.contains(id)
+            // Still waiting to be deleted from storage
+            || self.deleted.iter().any(|deleted| deleted == id)
+    }
+
+    /// Picks an id no live, stored or just-deleted game uses. If codes of the
+    /// configured length keep colliding, longer ones are tried.
+    fn generate_game_id<R: rand::Rng>(&self, rng: &mut R) -> String {
+        let mut length = self.id_length;
+        loop {
+            for _ in 0..ID_ATTEMPTS_PER_LENGTH {
+                let id = random_code(rng, length);
+                if !self.is_id_taken(&id) {
+                    return id;
+                }
+            }
+            tracing::warn!("Game ids of length {} keep colliding, trying longer ones", length);
+            length += 1;
+        }
+    }
+
+    /// Adds a session restored from storage.
+    pub fn restore_game(&mut self, mut session: GameSession) {
+        session.attach_channel();
+        session.dirty = false;
+        // Undo history isn't persisted, so a pending request can't be honoured
+        session.pending_undo = None;
+        if session.seats.len() != session.players.len() {
+            session.rebuild_seats();
+        }
+        // Nobody is connected right after a restart
+        session.spectators = 0;
+        // Games saved before activity was tracked get a fresh idle timer
+        if session.last_activity == 0 {
+            session.last_activity = unix_now();
+        }
+        for player in session.players.values_mut() {
+            player.is_active = false;
         }
+        self.games.insert(session.id.clone(), session);
     }
 
-    pub fn create_game(&mut self) -> String {
-        let game_id = generate_short_id();
-        self.games.insert(game_id.clone(), GameSession::new(game_id.clone()));
+    /// Serializes every changed session and clears its dirty flag.
+    /// Returns `(saved, deleted)` game ids/snapshots to write to storage.
+    pub fn take_pending_saves(&mut self) -> (Vec<(String, String)>, Vec<String>) {
+        let mut saved = Vec::new();
+        for game in self.games.values_mut().filter(|g| g.dirty) {
+            match serde_json::to_string(&*game) {
+                Ok(json) => {
+                    saved.push((game.id.clone(), json));
+                    game.dirty = false;
+                }
+                Err(e) => tracing::error!("Failed to serialize game {}: {}", game.id, e),
+            }
+        }
+        (saved, std::mem::take(&mut self.deleted))
+    }
+
+    pub fn create_game(&mut self, settings: GameSettings) -> String {
+        let game_id = self.generate_game_id(&mut rand::thread_rng());
+        let mut game = GameSession::new(game_id.clone());
+        game.settings = settings;
+        self.games.insert(game_id.clone(), game);
         game_id
     }
 
+    /// Public games with a free seat, newest first.
+    pub fn open_games(&self) -> Vec<GameListing> {
+        let mut games: Vec<GameListing> = self.games
+            .values()
+            .filter(|game| game.settings.is_public && !game.is_full())
+            .map(GameSession::listing)
+            .collect();
+        games.sort_by_key(|game| std::cmp::Reverse(game.created_at));
+        games
+    }
+
     pub fn get_game(&self, game_id: &str) -> Option<&GameSession> {
         self.games.get(game_id)
     }
@@ -284,18 +2058,726 @@ impl GameManager {
         self.games.get_mut(game_id)
     }
 
+    pub fn games_mut(&mut self) -> impl Iterator<Item = &mut GameSession> {
+        self.games.values_mut()
+    }
+
+    /// Takes a game out of memory without deleting it from storage, so its
+    /// saved row can be archived instead. The id stays reserved.
+    pub fn expire_game(&mut self, game_id: &str) -> Option<GameSession> {
+        let game = self.games.remove(game_id)?;
+        self.reserved_ids.insert(game_id.to_string());
+        Some(game)
+    }
+
     pub fn delete_game(&mut self, game_id: &str) {
-        self.games.remove(game_id);
+        if self.games.remove(game_id).is_some() {
+            self.deleted.push(game_id.to_string());
+        }
     }
 }
 
-fn generate_short_id() -> String {
-    use std::fmt::Write;
-    let uuid = Uuid::new_v4();
-    let bytes = uuid.as_bytes();
-    let mut result = String::new();
-    for i in 0..2 {
-        write!(&mut result, "{:02X}", bytes[i]).unwrap();
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn random_code<R: rand::Rng>(rng: &mut R, length: usize) -> String {
+    (0..length)
+        .map(|_| GAME_ID_ALPHABET[rng.gen_range(0..GAME_ID_ALPHABET.len())] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_with(ids: &[&str]) -> GameSession {
+        let mut game = GameSession::new("test".to_string());
+        for id in ids {
+            game.add_player(Player::new(id.to_string(), id.to_string(), 0));
+        }
+        game
+    }
+
+    fn active(game: &GameSession) -> &str {
+        game.active_player_id().unwrap()
+    }
+
+    #[test]
+    fn seats_follow_join_order() {
+        let game = game_with(&["a", "b", "c", "d"]);
+        assert_eq!(game.seats, vec!["a", "b", "c", "d"]);
+        assert_eq!(game.players["c"].join_order, 2);
+    }
+
+    #[test]
+    fn next_turn_rotates_in_seat_order_and_counts_rounds() {
+        let mut game = game_with(&["a", "b", "c"]);
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            game.next_turn();
+            order.push(active(&game).to_string());
+        }
+        assert_eq!(order, vec!["b", "c", "a", "b"]);
+        assert_eq!(game.turn_number, 2);
+        assert!(game.players["b"].is_active);
+        assert!(!game.players["a"].is_active);
+    }
+
+    #[test]
+    fn undo_turn_steps_back_across_rounds() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.undo_turn();
+        assert_eq!(active(&game), "a", "can't go back before the first turn");
+
+        game.next_turn();
+        game.next_turn();
+        game.next_turn();
+        assert_eq!((active(&game), game.turn_number), ("a", 2));
+
+        game.undo_turn();
+        assert_eq!((active(&game), game.turn_number), ("c", 1));
+    }
+
+    #[test]
+    fn leaving_player_is_skipped() {
+        let mut game = game_with(&["a", "b", "c", "d"]);
+        game.next_turn();
+        game.remove_player("c");
+        assert_eq!(game.seats, vec!["a", "b", "d"]);
+        assert_eq!(game.players["d"].join_order, 2);
+
+        game.next_turn();
+        assert_eq!(active(&game), "d");
+    }
+
+    #[test]
+    fn active_player_leaving_passes_the_turn() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.next_turn();
+        game.remove_player("b");
+        assert_eq!(active(&game), "c");
+
+        game.remove_player("c");
+        assert_eq!((active(&game), game.turn_number), ("a", 2));
+    }
+
+    #[test]
+    fn reorder_keeps_the_active_player() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.next_turn();
+        game.reorder_seats(vec!["c".into(), "a".into(), "b".into()]).unwrap();
+        assert_eq!(active(&game), "b");
+        assert_eq!(game.players["c"].join_order, 0);
+
+        game.next_turn();
+        assert_eq!(active(&game), "c");
+
+        assert!(game.reorder_seats(vec!["a".into(), "b".into()]).is_err());
+        assert!(game.reorder_seats(vec!["a".into(), "b".into(), "b".into()]).is_err());
+    }
+
+    #[test]
+    fn random_start_rotates_without_changing_neighbours() {
+        let mut game = game_with(&["a", "b", "c", "d"]);
+        game.next_turn();
+        let first = game.randomize_starting_player().unwrap();
+        assert_eq!(active(&game), first);
+        assert_eq!(game.turn_number, 1);
+
+        let offset = ["a", "b", "c", "d"].iter().position(|id| *id == first).unwrap();
+        let mut expected = vec!["a", "b", "c", "d"];
+        expected.rotate_left(offset);
+        assert_eq!(game.seats, expected);
+    }
+
+    fn card(id: &str) -> Card {
+        Card {
+            id: id.to_string(),
+            name: id.to_string(),
+            is_tapped: false,
+            is_flipped: false,
+            is_commander: false,
+            is_token: false,
+            is_two_sided: false,
+            is_back_face: false,
+            set_code: None,
+            collector_number: None,
+            position_x: 0.0,
+            position_y: 0.0,
+            is_revealed: false,
+            counters: BTreeMap::new(),
+            attached_to: None,
+            is_aura: false,
+            owner_id: None,
+        }
+    }
+
+    #[test]
+    fn steps_run_through_the_turn_and_pass_it() {
+        let mut game = game_with(&["a", "b"]);
+        game.advance_step();
+        assert_eq!(game.step, Step::Upkeep);
+        for _ in 0..10 {
+            game.advance_step();
+        }
+        assert_eq!(game.step, Step::Cleanup);
+        assert_eq!(active(&game), "a");
+
+        game.advance_step();
+        assert_eq!((game.step, active(&game)), (Step::Untap, "b"));
+    }
+
+    #[test]
+    fn automation_untaps_and_draws_for_the_active_player() {
+        let mut game = game_with(&["a", "b"]);
+        game.automation = TurnAutomation { auto_untap: true, auto_draw: true };
+        for id in ["a", "b"] {
+            let player = game.get_player_mut(id).unwrap();
+            player.library = vec![card("1"), card("2")];
+            player.battlefield = vec![Card { is_tapped: true, ..card("land") }];
+        }
+
+        game.next_turn();
+        assert!(!game.players["b"].battlefield[0].is_tapped);
+        assert!(game.players["a"].battlefield[0].is_tapped);
+
+        game.advance_step();
+        game.advance_step();
+        assert_eq!(game.step, Step::Draw);
+        assert_eq!(game.players["b"].hand.len(), 1);
+        assert!(game.players["a"].hand.is_empty());
+    }
+
+    #[test]
+    fn counters_stay_on_the_battlefield_only() {
+        let mut game = game_with(&["a"]);
+        game.get_player_mut("a").unwrap().battlefield.push(card("bear"));
+
+        assert_eq!(game.update_card_counter("a", "bear", "+1/+1", 2), Ok(2));
+        assert_eq!(game.update_card_counter("a", "bear", "+1/+1", -5), Ok(0));
+        assert!(game.players["a"].battlefield[0].counters.is_empty());
+
+        game.update_card_counter("a", "bear", "+1/+1", 1).unwrap();
+        game.move_card("a", "bear", Zone::Battlefield, Zone::Battlefield).unwrap();
+        assert_eq!(game.players["a"].battlefield[0].counters["+1/+1"], 1);
+
+        game.move_card("a", "bear", Zone::Battlefield, Zone::Graveyard).unwrap();
+        assert!(game.players["a"].graveyard[0].counters.is_empty());
+        assert!(game.update_card_counter("a", "bear", "+1/+1", 1).is_err());
+    }
+
+    #[test]
+    fn commander_damage_is_tracked_per_commander() {
+        let mut game = game_with(&["a", "b"]);
+        for (owner, id) in [("a", "atraxa"), ("a", "thrasios")] {
+            game.get_player_mut(owner).unwrap().command_zone.push(Card { is_commander: true, ..card(id) });
+        }
+        game.get_player_mut("a").unwrap().battlefield.push(card("bear"));
+
+        assert!(game.update_commander_damage("b", "bear", 5).is_err());
+
+        assert_eq!(game.update_commander_damage("b", "atraxa", 15), Ok(15));
+        assert_eq!(game.update_commander_damage("b", "thrasios", 10), Ok(10));
+        assert_eq!(game.players["b"].life, 15);
+        assert!(!game.players["b"].is_eliminated);
+
+        assert_eq!(game.update_commander_damage("b", "atraxa", 6), Ok(21));
+        assert!(game.players["b"].is_eliminated);
+        assert_eq!(game.commander_damage["b"]["atraxa"], 21);
+
+        assert_eq!(game.update_commander_damage("b", "thrasios", -20), Ok(0));
+        assert_eq!(game.players["b"].life, 19);
+        assert!(!game.commander_damage["b"].contains_key("thrasios"));
+    }
+
+    #[test]
+    fn loss_conditions_eliminate_automatically() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.update_life("b", -40).unwrap();
+        assert_eq!(game.players["b"].elimination_reason, Some(EliminationReason::Life));
+
+        game.update_counter("c", "poison", 9).unwrap();
+        assert!(!game.players["c"].is_eliminated);
+        game.update_counter("c", "poison", 1).unwrap();
+        assert_eq!(game.players["c"].elimination_reason, Some(EliminationReason::Poison));
+
+        game.update_counter("a", "energy", 20).unwrap();
+        assert!(!game.players["a"].is_eliminated);
+    }
+
+    #[test]
+    fn eliminated_players_are_skipped_until_reinstated() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.eliminate("b", EliminationReason::Conceded).unwrap();
+        game.next_turn();
+        assert_eq!(active(&game), "c");
+
+        game.reinstate("b").unwrap();
+        assert!(game.reinstate("b").is_err());
+        game.next_turn();
+        game.next_turn();
+        assert_eq!(active(&game), "b");
+    }
+
+    #[test]
+    fn eliminating_the_active_player_passes_the_turn() {
+        let mut game = game_with(&["a", "b"]);
+        game.eliminate("a", EliminationReason::Conceded).unwrap();
+        assert_eq!(active(&game), "b");
+        assert!(game.eliminate("a", EliminationReason::Life).is_err());
+    }
+
+    #[test]
+    fn attachments_follow_their_host() {
+        let mut game = game_with(&["a", "b"]);
+        let board = &mut game.get_player_mut("a").unwrap().battlefield;
+        board.push(Card { position_x: 100.0, position_y: 100.0, ..card("knight") });
+        board.push(card("sword"));
+        board.push(card("aura"));
+        game.get_player_mut("b").unwrap().battlefield.push(card("dragon"));
+
+        assert!(game.attach_card("a", "sword", "sword", false).is_err());
+        game.attach_card("a", "sword", "knight", false).unwrap();
+        game.attach_card("a", "aura", "sword", true).unwrap();
+        assert!(game.attach_card("a", "knight", "aura", false).is_err(), "cycles are refused");
+
+        let sword = &game.players["a"].battlefield[1];
+        assert_eq!((sword.position_x, sword.position_y), (115.0, 115.0));
+
+        game.move_on_battlefield("a", "knight", 200.0, 50.0).unwrap();
+        let board = &game.players["a"].battlefield;
+        assert_eq!((board[1].position_x, board[1].position_y), (215.0, 65.0));
+        assert_eq!((board[2].position_x, board[2].position_y), (230.0, 80.0));
+
+        // Attaching to another player's permanent doesn't move the card
+        game.attach_card("a", "aura", "dragon", true).unwrap();
+        assert_eq!(game.players["a"].battlefield[2].position_x, 230.0);
+        game.detach_card("a", "aura").unwrap();
+        assert!(game.detach_card("a", "aura").is_err());
+        game.attach_card("a", "aura", "sword", true).unwrap();
+
+        game.move_card("a", "knight", Zone::Battlefield, Zone::Graveyard).unwrap();
+        let board = &game.players["a"].battlefield;
+        assert_eq!(board.len(), 2);
+        assert!(board.iter().all(|c| c.attached_to.is_none() || c.id == "aura"));
+        assert_eq!(game.players["a"].graveyard.len(), 1);
+    }
+
+    #[test]
+    fn auras_go_to_the_graveyard_with_their_host() {
+        let mut game = game_with(&["a", "b"]);
+        game.get_player_mut("a").unwrap().battlefield.push(card("aura"));
+        game.get_player_mut("b").unwrap().battlefield.push(card("dragon"));
+        game.attach_card("a", "aura", "dragon", true).unwrap();
+        game.update_card_counter("a", "aura", "time", 2).unwrap();
+
+        game.move_card("b", "dragon", Zone::Battlefield, Zone::Exile).unwrap();
+        assert!(game.players["a"].battlefield.is_empty());
+        let aura = &game.players["a"].graveyard[0];
+        assert!(aura.attached_to.is_none() && aura.counters.is_empty());
+    }
+
+    #[test]
+    fn control_changes_remember_the_owner() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.get_player_mut("a").unwrap().battlefield.push(card("angel"));
+
+        assert!(game.gain_control("c", "angel", "b").is_err(), "bystanders can't move control");
+        game.gain_control("b", "angel", "b").unwrap();
+        assert!(game.players["a"].battlefield.is_empty());
+        assert_eq!(game.players["b"].battlefield[0].owner_id.as_deref(), Some("a"));
+
+        game.return_control("b", "angel").unwrap();
+        assert!(game.players["a"].battlefield[0].owner_id.is_none());
+        assert!(game.return_control("a", "angel").is_err());
+
+        game.gain_control("b", "angel", "b").unwrap();
+        game.move_card("b", "angel", Zone::Battlefield, Zone::Graveyard).unwrap();
+        assert!(game.players["b"].graveyard.is_empty());
+        assert!(game.players["a"].graveyard[0].owner_id.is_none());
+    }
+
+    #[test]
+    fn borrowed_cards_go_home_when_a_player_leaves() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.get_player_mut("a").unwrap().battlefield.push(card("angel"));
+        game.get_player_mut("b").unwrap().battlefield.push(card("bear"));
+        game.gain_control("b", "angel", "b").unwrap();
+        game.gain_control("c", "bear", "c").unwrap();
+
+        game.remove_player("b");
+        assert_eq!(game.players["a"].battlefield[0].id, "angel");
+        assert!(game.players["c"].battlefield.is_empty(), "the leaver's own cards leave too");
+    }
+
+    #[test]
+    fn spells_resolve_from_the_top_of_the_stack() {
+        let mut game = game_with(&["a", "b"]);
+        game.get_player_mut("a").unwrap().hand = vec![card("bear"), card("bolt")];
+        game.get_player_mut("b").unwrap().hand = vec![card("counterspell")];
+
+        game.cast_spell("a", "bear", Zone::Hand, true).unwrap();
+        game.cast_spell("a", "bolt", Zone::Hand, false).unwrap();
+        game.cast_spell("b", "counterspell", Zone::Hand, false).unwrap();
+        assert!(game.cast_spell("a", "bear", Zone::Hand, true).is_err());
+
+        assert_eq!(game.resolve_stack().unwrap().card.id, "counterspell");
+        assert_eq!(game.players["b"].graveyard[0].id, "counterspell");
+
+        game.counter_spell("bolt").unwrap();
+        assert_eq!(game.players["a"].graveyard[0].id, "bolt");
+
+        game.resolve_stack().unwrap();
+        assert_eq!(game.players["a"].battlefield[0].id, "bear");
+        assert!(game.resolve_stack().is_err());
+    }
+
+    #[test]
+    fn stack_can_be_reordered() {
+        let mut game = game_with(&["a"]);
+        game.get_player_mut("a").unwrap().hand = vec![card("x"), card("y"), card("z")];
+        for id in ["x", "y", "z"] {
+            game.cast_spell("a", id, Zone::Hand, false).unwrap();
+        }
+        assert!(game.reorder_stack(&["x".into(), "y".into()]).is_err());
+        game.reorder_stack(&["z".into(), "x".into(), "y".into()]).unwrap();
+        assert_eq!(game.resolve_stack().unwrap().card.id, "y");
+    }
+
+    #[test]
+    fn face_down_spells_are_hidden_from_opponents() {
+        let mut game = game_with(&["a", "b"]);
+        game.get_player_mut("a").unwrap().hand = vec![Card { is_flipped: true, ..card("morph") }];
+        game.cast_spell("a", "morph", Zone::Hand, true).unwrap();
+        assert_eq!(game.view_for("a").stack[0].card.name, "morph");
+        assert_eq!(game.view_for("b").stack[0].card.name, "");
+    }
+
+    /// A game with one undoable life change in its history.
+    fn game_with_history(ids: &[&str]) -> GameSession {
+        let mut game = game_with_libraries(ids);
+        let before = game.board_clone();
+        game.update_life(ids[0], -3).unwrap();
+        game.push_history(ids[0], "lost life".to_string(), before);
+        game
+    }
+
+    #[test]
+    fn a_player_joining_clears_undo_history() {
+        let mut game = game_with_history(&["a"]);
+        game.add_player(Player::new("b".to_string(), "B".to_string(), 1));
+        assert!(game.request_undo("a", 1).is_err());
+        assert!(game.players.contains_key("b"));
+    }
+
+    #[test]
+    fn starting_mulligans_clears_undo_history() {
+        let mut game = game_with_history(&["a", "b"]);
+        game.start_mulligans(false).unwrap();
+        assert!(game.request_undo("a", 1).is_err());
+        assert!(game.mulligan.is_some());
+    }
+
+    #[test]
+    fn mulligans_only_start_once_and_on_the_first_turn() {
+        let mut game = game_with_libraries(&["a", "b"]);
+        game.start_mulligans(false).unwrap();
+        assert!(game.start_mulligans(false).is_err());
+
+        let mut game = game_with_libraries(&["a", "b"]);
+        game.turn_number = 2;
+        assert!(game.start_mulligans(false).is_err());
+        assert!(game.mulligan.is_none());
+        assert!(game.players["a"].hand.is_empty());
+    }
+
+    #[test]
+    fn restarting_clears_undo_history() {
+        let mut game = game_with_history(&["a", "b"]);
+        game.restart_game();
+        assert!(game.request_undo("a", 1).is_err());
+        assert_eq!(game.turn_number, 1);
+    }
+
+    #[test]
+    fn spectators_see_no_hidden_cards() {
+        let mut game = game_with(&["a", "b"]);
+        game.get_player_mut("a").unwrap().hand = vec![card("x")];
+        game.get_player_mut("b").unwrap().battlefield = vec![Card { is_flipped: true, ..card("morph") }];
+        let view = game.view_for(SPECTATOR_VIEWER);
+        assert_eq!(view.players["a"].hand[0].name, "");
+        assert_eq!(view.players["b"].battlefield[0].name, "");
+    }
+
+    #[test]
+    fn undo_leaves_the_spectator_count_alone() {
+        let mut game = game_with(&["a"]);
+        let before = game.board_clone();
+        game.update_life("a", -3).unwrap();
+        game.push_history("a", "lost life".to_string(), before);
+        let version = game.version;
+        game.set_spectating(true);
+        assert_eq!(game.version, version);
+        game.request_undo("a", 1).unwrap();
+        assert_eq!(game.players["a"].life, 40);
+        assert_eq!(game.spectators, 1);
+    }
+
+    #[test]
+    fn seats_need_room_and_the_password() {
+        let mut game = game_with(&["a", "b"]);
+        game.settings.max_players = 3;
+        game.settings.host_id = Some("host".to_string());
+        game.settings.password_hash = Some("hash".to_string());
+
+        assert!(game.can_seat("a").is_ok());
+        assert!(game.can_seat("host").is_ok());
+        assert!(game.can_seat("c").is_err());
+        game.admitted.insert("c".to_string());
+        assert!(game.can_seat("c").is_ok());
+
+        game.add_player(Player::new("c".to_string(), "C".to_string(), 2));
+        assert_eq!(game.can_seat("d"), Err("This game is full".to_string()));
+        assert!(game.view_for("a").settings.password_hash.is_none());
+    }
+
+    #[test]
+    fn members_are_players_the_host_and_admitted_users() {
+        let mut game = game_with(&["a"]);
+        game.settings.host_id = Some("host".to_string());
+        game.admitted.insert("guest".to_string());
+        for id in ["a", "host", "guest"] {
+            assert!(game.is_member(id), "{} should be a member", id);
+        }
+        assert!(!game.is_member("stranger"));
+        assert!(!game.is_member(""));
+    }
+
+    #[test]
+    fn private_and_password_games_are_only_watched_by_members() {
+        let mut game = game_with(&["a"]);
+        assert!(game.can_spectate("stranger").is_ok());
+
+        game.settings.is_public = false;
+        assert!(game.can_spectate("stranger").is_err());
+        assert!(game.can_spectate("a").is_ok());
+
+        game.settings.is_public = true;
+        game.settings.password_hash = Some("hash".to_string());
+        assert_eq!(game.can_spectate("stranger"), Err("This game needs a password".to_string()));
+        game.admitted.insert("stranger".to_string());
+        assert!(game.can_spectate("stranger").is_ok());
+    }
+
+    #[test]
+    fn only_public_games_with_room_are_listed() {
+        let mut gm = GameManager::new();
+        let open = gm.create_game(GameSettings::default());
+        gm.create_game(GameSettings { is_public: false, ..GameSettings::default() });
+        let full = gm.create_game(GameSettings { max_players: 2, ..GameSettings::default() });
+        let game = gm.get_game_mut(&full).unwrap();
+        for (i, id) in ["a", "b"].into_iter().enumerate() {
+            game.add_player(Player::new(id.to_string(), id.to_string(), i));
+        }
+
+        let listed: Vec<String> = gm.open_games().into_iter().map(|g| g.game_id).collect();
+        assert_eq!(listed, vec![open]);
+    }
+
+    #[test]
+    fn game_ids_are_readable_and_never_reused() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut gm = GameManager::new().with_id_length(5);
+        let id = gm.create_game(GameSettings::default());
+        assert_eq!(id.len(), 5);
+        assert!(id.bytes().all(|b| GAME_ID_ALPHABET.contains(&b)));
+        assert_eq!(GameManager::new().with_id_length(100).id_length, MAX_GAME_ID_LENGTH);
+
+        // The same seed would give the same id again, so it has to be skipped
+        let first = gm.generate_game_id(&mut StdRng::seed_from_u64(1));
+        gm.reserve_ids([first.clone()]);
+        assert_ne!(gm.generate_game_id(&mut StdRng::seed_from_u64(1)), first);
+    }
+
+    #[test]
+    fn seats_are_rebuilt_from_join_order() {
+        let mut game = game_with(&["a", "b", "c"]);
+        game.seats.clear();
+        game.rebuild_seats();
+        assert_eq!(game.seats, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn related_tokens_are_created_next_to_their_source() {
+        let mut game = game_with(&["a", "b"]);
+        game.get_player_mut("a").unwrap().battlefield.push(card("krenko"));
+        let goblin = crate::tokens::RelatedToken {
+            name: "Goblin".to_string(),
+            set_code: "tm19".to_string(),
+            collector_number: "11".to_string(),
+            is_two_sided: false,
+        };
+
+        assert!(game.create_tokens("a", "krenko", &goblin, 0).is_err());
+        assert!(game.create_tokens("b", "krenko", &goblin, 1).is_err());
+        let ids = game.create_tokens("a", "krenko", &goblin, 3).unwrap();
+        let battlefield = &game.players["a"].battlefield;
+        assert_eq!(ids.len(), 3);
+        assert_eq!(battlefield.len(), 4);
+        assert!(battlefield[1..].iter().all(|c| c.is_token && c.name == "Goblin"));
+        assert_eq!(battlefield[3].position_x, battlefield[0].position_x + TOKEN_OFFSET.0 * 3.0);
+    }
+
+    fn game_with_libraries(ids: &[&str]) -> GameSession {
+        let mut game = game_with(ids);
+        for id in ids {
+            let library = (0..20).map(|i| card(&format!("{}{}", id, i))).collect();
+            game.get_player_mut(id).unwrap().library = library;
+        }
+        game
+    }
+
+    #[test]
+    fn london_mulligan_bottoms_one_card_per_mulligan() {
+        let mut game = game_with_libraries(&["a", "b"]);
+        game.start_mulligans(false).unwrap();
+        assert_eq!(game.players["a"].hand.len(), OPENING_HAND_SIZE);
+        assert_eq!(game.players["a"].library.len(), 13);
+
+        assert_eq!(game.take_mulligan("a").unwrap(), 1);
+        assert_eq!(game.take_mulligan("a").unwrap(), 2);
+        assert_eq!(game.players["a"].hand.len(), OPENING_HAND_SIZE);
+        let hand: Vec<String> = game.players["a"].hand.iter().map(|c| c.id.clone()).collect();
+        assert!(game.keep_hand("a", &hand[..1]).is_err());
+        assert!(game.keep_hand("a", &[hand[0].clone(), hand[0].clone()]).is_err());
+        game.keep_hand("a", &hand[..2]).unwrap();
+        assert_eq!(game.players["a"].hand.len(), 5);
+        assert_eq!(game.players["a"].library[0].id, hand[1]);
+        assert!(game.take_mulligan("a").is_err());
+
+        assert_eq!(game.mulligan.as_ref().unwrap().deciding(), vec!["b"]);
+        game.keep_hand("b", &[]).unwrap();
+        assert!(game.mulligan.is_none());
+    }
+
+    #[test]
+    fn free_first_mulligan_and_leavers_release_the_game() {
+        let mut game = game_with_libraries(&["a", "b", "c"]);
+        game.start_mulligans(true).unwrap();
+        game.take_mulligan("a").unwrap();
+        game.keep_hand("a", &[]).unwrap();
+        game.keep_hand("b", &[]).unwrap();
+
+        game.eliminate("c", EliminationReason::Conceded).unwrap();
+        assert!(game.mulligan.is_none());
+    }
+
+    fn ids(cards: &[Card]) -> Vec<&str> {
+        cards.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn the_top_of_the_library_is_its_end() {
+        let mut game = game_with_libraries(&["a"]);
+        let player = game.get_player_mut("a").unwrap();
+        player.draw_cards(2);
+        assert_eq!(ids(&player.hand), vec!["a19", "a18"]);
+        player.exile_top_face_down(2);
+        assert_eq!(ids(&player.exile), vec!["a17", "a16"]);
+        assert!(player.exile.iter().all(|c| c.is_flipped));
+    }
+
+    #[test]
+    fn scrying_to_the_bottom_changes_the_next_draw() {
+        let mut game = game_with_libraries(&["a"]);
+        let player = game.get_player_mut("a").unwrap();
+        let seen: Vec<String> = player.library.iter().rev().take(1).map(|c| c.id.clone()).collect();
+        assert_eq!(seen, vec!["a19"]);
+        player.finish_scry(&[], &seen);
+        assert_eq!(player.library[0].id, "a19");
+        player.draw_cards(1);
+        assert_eq!(ids(&player.hand), vec!["a18"]);
+    }
+
+    #[test]
+    fn scrying_keeps_the_chosen_order_on_top() {
+        let mut game = game_with_libraries(&["a"]);
+        let player = game.get_player_mut("a").unwrap();
+        let len = player.library.len();
+        player.finish_scry(&["a18".to_string(), "a19".to_string()], &["a17".to_string()]);
+        assert_eq!(player.library.len(), len);
+        assert_eq!(player.library[0].id, "a17");
+        player.draw_cards(3);
+        assert_eq!(ids(&player.hand), vec!["a18", "a19", "a16"]);
+    }
+
+    #[test]
+    fn surveilled_cards_reach_the_graveyard() {
+        let mut game = game_with_libraries(&["a"]);
+        let player = game.get_player_mut("a").unwrap();
+        let len = player.library.len();
+        player.finish_surveil(&["a18".to_string()], &["a19".to_string()]);
+        assert_eq!(ids(&player.graveyard), vec!["a19"]);
+        assert_eq!(player.library.len(), len - 1);
+        player.draw_cards(1);
+        assert_eq!(ids(&player.hand), vec!["a18"]);
+    }
+
+    #[test]
+    fn cards_moved_to_the_library_go_on_top() {
+        let mut game = game_with_libraries(&["a"]);
+        game.get_player_mut("a").unwrap().hand.push(card("x"));
+        game.move_card("a", "x", Zone::Hand, Zone::Library).unwrap();
+        assert_eq!(game.players["a"].library.last().unwrap().id, "x");
+
+        let player = game.get_player_mut("a").unwrap();
+        player.draw_cards(1);
+        assert_eq!(ids(&player.hand), vec!["x"]);
+    }
+
+    #[test]
+    fn cards_can_be_put_nth_from_the_top_or_bottom() {
+        let mut game = game_with_libraries(&["a"]);
+        game.get_player_mut("a").unwrap().hand.push(card("x"));
+        game.put_in_library("a", "x", Zone::Hand, 3, false).unwrap();
+        let library = &game.players["a"].library;
+        assert_eq!(library[library.len() - 3].id, "x");
+
+        game.put_in_library("a", "x", Zone::Library, 2, true).unwrap();
+        assert_eq!(game.players["a"].library[1].id, "x");
+
+        assert!(game.put_in_library("a", "x", Zone::Library, 0, false).is_err());
+        game.put_in_library("a", "x", Zone::Library, 99, false).unwrap();
+        assert_eq!(game.players["a"].library[0].id, "x");
+    }
+
+    #[test]
+    fn tutoring_and_revealing_until_a_card() {
+        let mut game = game_with_libraries(&["a"]);
+        let player = game.get_player_mut("a").unwrap();
+        player.library[16].name = "Forest".to_string();
+
+        assert!(player.tutor("nope", &Zone::Hand, true).is_err());
+        let found = player.tutor("a5", &Zone::Hand, false).unwrap();
+        assert_eq!(found.id, "a5");
+        assert_eq!(player.hand[0].id, "a5");
+
+        let (revealed, hit) = player.reveal_until("forest", &Zone::Battlefield, &Zone::Graveyard).unwrap();
+        assert!(hit);
+        assert_eq!(ids(&revealed), vec!["a19", "a18", "a17", "a16"]);
+        assert_eq!(player.battlefield[0].name, "Forest");
+        assert_eq!(ids(&player.graveyard), vec!["a19", "a18", "a17"]);
+
+        let before = player.library.len();
+        let (revealed, hit) = player.reveal_until("Island", &Zone::Hand, &Zone::Library).unwrap();
+        assert!(!hit);
+        assert_eq!(revealed.len(), before);
+        assert_eq!(player.library.len(), before);
     }
-    result
 }
@@ -1,12 +1,14 @@
 use axum::{
-    extract::{State, Query},
-    http::StatusCode,
+    extract::{Path, State, Query},
+    http::{header, HeaderMap, StatusCode},
     response::Json,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::users::{LoginRequest, RegisterRequest, ResetPasswordRequest, AuthResponse, create_user, verify_user, user_exists, reset_password};
+use crate::decks::{self, SaveDeckRequest, DeckResponse, DeckListResponse};
+use crate::users::{LoginRequest, LogoutRequest, RegisterRequest, ResetPasswordRequest, AuthResponse, create_user, verify_user, user_exists, reset_password, create_session, delete_session, user_for_token, User};
+use crate::game::{GameSettings, DEFAULT_MAX_PLAYERS, MAX_PLAYERS_LIMIT};
 use crate::AppState;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,21 +30,226 @@ pub async fn health_handler() -> &'static str {
     "OK"
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateGameRequest {
+    #[serde(default)]
+    pub is_public: Option<bool>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub max_players: Option<usize>,
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Creates a game. A JSON body may set its lobby settings; without one the
+/// game is public, has no password and seats `DEFAULT_MAX_PLAYERS`.
 pub async fn create_game_handler(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    payload: Option<Json<CreateGameRequest>>,
 ) -> (StatusCode, Json<serde_json::Value>) {
+    let request = payload.map(|Json(request)| request).unwrap_or_default();
+
+    let max_players = request.max_players.unwrap_or(DEFAULT_MAX_PLAYERS);
+    if !(2..=MAX_PLAYERS_LIMIT).contains(&max_players) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "success": false,
+                "message": format!("Games seat 2 to {} players", MAX_PLAYERS_LIMIT),
+            })),
+        );
+    }
+
+    let password_hash = match request.password.filter(|p| !p.is_empty()) {
+        Some(password) => match bcrypt::hash(password, 10) {
+            Ok(hash) => Some(hash),
+            Err(e) => {
+                tracing::error!("Failed to hash game password: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "success": false, "message": "Failed to create game" })),
+                );
+            }
+        },
+        None => None,
+    };
+
+    // Creating a game doesn't need an account, but a logged-in creator is its host
+    let host = authenticated_user(&state, &headers).await.ok();
+    let defaults = GameSettings::default();
+    let settings = GameSettings {
+        host_id: host.as_ref().map(|user| user.id.clone()),
+        host_name: host.map(|user| user.username),
+        is_public: request.is_public.unwrap_or(defaults.is_public),
+        password_hash,
+        max_players,
+        format: request.format.filter(|f| !f.trim().is_empty()).unwrap_or(defaults.format),
+    };
+
     let mut gm = state.game_manager.write().await;
-    let game_id = gm.create_game();
+    let game_id = gm.create_game(settings);
 
     (
         StatusCode::CREATED,
         Json(json!({
+            "success": true,
             "game_id": game_id,
             "message": "Game created successfully"
         })),
     )
 }
 
+/// Lists public games that still have a free seat.
+pub async fn list_games_handler(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let gm = state.game_manager.read().await;
+    (
+        StatusCode::OK,
+        Json(json!({
+            "success": true,
+            "games": gm.open_games(),
+        })),
+    )
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct JoinGameRequest {
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+fn join_error_status(message: &str) -> StatusCode {
+    if message.contains("not found") {
+        StatusCode::NOT_FOUND
+    } else if message.contains("full") {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::FORBIDDEN
+    }
+}
+
+/// Checks a game's password for the caller and, if it matches, lets them take
+/// a seat or watch when they connect. A full game still admits the caller as
+/// a spectator but answers with a conflict.
+pub async fn join_game_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(game_id): Path<String>,
+    payload: Option<Json<JoinGameRequest>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let user = match authenticated_user(&state, &headers).await {
+        Ok(user) => user,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(json!({ "success": false, "message": e }))),
+    };
+    let password = payload.and_then(|Json(request)| request.password).unwrap_or_default();
+
+    let result = {
+        let gm = state.game_manager.read().await;
+        match gm.get_game(&game_id) {
+            Some(game) if game.is_member(&user.id) => Ok(None),
+            Some(game) => Ok(game.settings.password_hash.clone()),
+            None => Err("Game not found".to_string()),
+        }
+    };
+
+    // Verified outside the lock since bcrypt is deliberately slow
+    let result = result.and_then(|hash| match hash {
+        Some(hash) if !bcrypt::verify(&password, &hash).unwrap_or(false) => {
+            Err("Wrong password".to_string())
+        }
+        _ => Ok(()),
+    });
+
+    let result = match result {
+        Ok(()) => {
+            let mut gm = state.game_manager.write().await;
+            match gm.get_game_mut(&game_id) {
+                Some(game) => {
+                    game.admitted.insert(user.id.clone());
+                    // A seat may have gone while the password was checked
+                    game.can_seat(&user.id)
+                }
+                None => Err("Game not found".to_string()),
+            }
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(json!({
+                "success": true,
+                "game_id": game_id,
+                "message": "You can join this game",
+            })),
+        ),
+        Err(e) => (
+            join_error_status(&e),
+            Json(json!({ "success": false, "message": e })),
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    #[serde(default)]
+    pub since: u64,
+}
+
+/// Returns a game's action log, redacted for the caller. Only players,
+/// the host and users who passed the join check may read it.
+pub async fn game_events_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(game_id): Path<String>,
+    Query(params): Query<EventsQuery>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let viewer_id = match authenticated_user(&state, &headers).await {
+        Ok(user) => user.id,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({
+                    "success": false,
+                    "message": e,
+                    "events": [],
+                })),
+            )
+        }
+    };
+
+    let gm = state.game_manager.read().await;
+    match gm.get_game(&game_id) {
+        Some(game) if game.is_member(&viewer_id) => (
+            StatusCode::OK,
+            Json(json!({
+                "success": true,
+                "events": game.events_for(&viewer_id, params.since),
+            })),
+        ),
+        Some(_) => (
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "success": false,
+                "message": "Only players in this game can read its log",
+                "events": [],
+            })),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "success": false,
+                "message": "Game not found",
+                "events": [],
+            })),
+        ),
+    }
+}
+
 pub async fn register_handler(
     State(state): State<AppState>,
     Json(payload): Json<RegisterRequest>,
@@ -58,6 +265,7 @@ pub async fn register_handler(
                     success: false,
                     message: "Username already exists".to_string(),
                     user: None,
+                    token: None,
                 }),
             );
         }
@@ -68,6 +276,7 @@ pub async fn register_handler(
                     success: false,
                     message: format!("Database error: {}", e),
                     user: None,
+                    token: None,
                 }),
             );
         }
@@ -75,21 +284,38 @@ pub async fn register_handler(
     }
 
     // Create new user
-    match create_user(pool, &payload.username, &payload.password).await {
-        Ok(user) => (
+    let user = match create_user(pool, &payload.username, &payload.password).await {
+        Ok(user) => user,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(AuthResponse {
+                    success: false,
+                    message: format!("Failed to create user: {}", e),
+                    user: None,
+                    token: None,
+                }),
+            );
+        }
+    };
+
+    match create_session(pool, &user.id).await {
+        Ok(token) => (
             StatusCode::CREATED,
             Json(AuthResponse {
                 success: true,
                 message: "User created successfully".to_string(),
                 user: Some(user),
+                token: Some(token),
             }),
         ),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(AuthResponse {
                 success: false,
-                message: format!("Failed to create user: {}", e),
+                message: e,
                 user: None,
+                token: None,
             }),
         ),
     }
@@ -101,21 +327,66 @@ pub async fn login_handler(
 ) -> (StatusCode, Json<AuthResponse>) {
     let pool = state.db_pool.as_ref();
 
-    match verify_user(pool, &payload.username, &payload.password).await {
-        Ok(user) => (
+    let user = match verify_user(pool, &payload.username, &payload.password).await {
+        Ok(user) => user,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(AuthResponse {
+                    success: false,
+                    message: format!("Login failed: {}", e),
+                    user: None,
+                    token: None,
+                }),
+            );
+        }
+    };
+
+    match create_session(pool, &user.id).await {
+        Ok(token) => (
             StatusCode::OK,
             Json(AuthResponse {
                 success: true,
                 message: "Login successful".to_string(),
                 user: Some(user),
+                token: Some(token),
             }),
         ),
         Err(e) => (
-            StatusCode::UNAUTHORIZED,
+            StatusCode::INTERNAL_SERVER_ERROR,
             Json(AuthResponse {
                 success: false,
                 message: format!("Login failed: {}", e),
                 user: None,
+                token: None,
+            }),
+        ),
+    }
+}
+
+pub async fn logout_handler(
+    State(state): State<AppState>,
+    Json(payload): Json<LogoutRequest>,
+) -> (StatusCode, Json<AuthResponse>) {
+    let pool = state.db_pool.as_ref();
+
+    match delete_session(pool, &payload.token).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(AuthResponse {
+                success: true,
+                message: "Logged out".to_string(),
+                user: None,
+                token: None,
+            }),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(AuthResponse {
+                success: false,
+                message: format!("Logout failed: {}", e),
+                user: None,
+                token: None,
             }),
         ),
     }
@@ -127,6 +398,22 @@ pub async fn reset_password_handler(
 ) -> (StatusCode, Json<AuthResponse>) {
     let pool = state.db_pool.as_ref();
 
+    // Only the logged-in owner of the account may change its password
+    match user_for_token(pool, &payload.token).await {
+        Ok(user) if user.username == payload.username => {}
+        _ => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(AuthResponse {
+                    success: false,
+                    message: "Not authorized to reset this password".to_string(),
+                    user: None,
+                    token: None,
+                }),
+            );
+        }
+    }
+
     match reset_password(pool, &payload.username, &payload.new_password).await {
         Ok(_) => (
             StatusCode::OK,
@@ -134,6 +421,7 @@ pub async fn reset_password_handler(
                 success: true,
                 message: "Password reset successfully".to_string(),
                 user: None,
+                token: None,
             }),
         ),
         Err(e) => (
@@ -142,6 +430,7 @@ pub async fn reset_password_handler(
                 success: false,
                 message: format!("Password reset failed: {}", e),
                 user: None,
+                token: None,
             }),
         ),
     }
@@ -269,4 +558,178 @@ pub async fn search_cards_handler(
             )
         }
     }
-}
\ No newline at end of file
+}
+/// Resolves the user from an `Authorization: Bearer <token>` header.
+async fn authenticated_user(state: &AppState, headers: &HeaderMap) -> Result<User, String> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or("Missing session token")?;
+    user_for_token(state.db_pool.as_ref(), token).await
+}
+
+fn deck_error_status(message: &str) -> StatusCode {
+    if message.contains("not found") {
+        StatusCode::NOT_FOUND
+    } else if message.starts_with("Invalid") || message.contains("cannot be empty") {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+pub async fn create_deck_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<SaveDeckRequest>,
+) -> (StatusCode, Json<DeckResponse>) {
+    let user = match authenticated_user(&state, &headers).await {
+        Ok(user) => user,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(DeckResponse { success: false, message: e, deck: None })),
+    };
+    let pool = state.db_pool.as_ref();
+
+    match decks::create_deck(pool, &user.id, &payload.name, &payload.cards).await {
+        Ok(deck) => (
+            StatusCode::CREATED,
+            Json(DeckResponse {
+                success: true,
+                message: "Deck created successfully".to_string(),
+                deck: Some(deck),
+            }),
+        ),
+        Err(e) => (
+            deck_error_status(&e),
+            Json(DeckResponse {
+                success: false,
+                message: e,
+                deck: None,
+            }),
+        ),
+    }
+}
+
+pub async fn list_decks_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<DeckListResponse>) {
+    let user = match authenticated_user(&state, &headers).await {
+        Ok(user) => user,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(DeckListResponse { success: false, message: e, decks: Vec::new() })),
+    };
+    let pool = state.db_pool.as_ref();
+
+    match decks::list_decks(pool, &user.id).await {
+        Ok(decks) => (
+            StatusCode::OK,
+            Json(DeckListResponse {
+                success: true,
+                message: format!("Found {} decks", decks.len()),
+                decks,
+            }),
+        ),
+        Err(e) => (
+            deck_error_status(&e),
+            Json(DeckListResponse {
+                success: false,
+                message: e,
+                decks: Vec::new(),
+            }),
+        ),
+    }
+}
+
+pub async fn get_deck_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(deck_id): Path<String>,
+) -> (StatusCode, Json<DeckResponse>) {
+    let user = match authenticated_user(&state, &headers).await {
+        Ok(user) => user,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(DeckResponse { success: false, message: e, deck: None })),
+    };
+    let pool = state.db_pool.as_ref();
+
+    match decks::get_deck(pool, &user.id, &deck_id).await {
+        Ok(deck) => (
+            StatusCode::OK,
+            Json(DeckResponse {
+                success: true,
+                message: "Deck found".to_string(),
+                deck: Some(deck),
+            }),
+        ),
+        Err(e) => (
+            deck_error_status(&e),
+            Json(DeckResponse {
+                success: false,
+                message: e,
+                deck: None,
+            }),
+        ),
+    }
+}
+
+pub async fn update_deck_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(deck_id): Path<String>,
+    Json(payload): Json<SaveDeckRequest>,
+) -> (StatusCode, Json<DeckResponse>) {
+    let user = match authenticated_user(&state, &headers).await {
+        Ok(user) => user,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(DeckResponse { success: false, message: e, deck: None })),
+    };
+    let pool = state.db_pool.as_ref();
+
+    match decks::update_deck(pool, &user.id, &deck_id, &payload.name, &payload.cards).await {
+        Ok(deck) => (
+            StatusCode::OK,
+            Json(DeckResponse {
+                success: true,
+                message: "Deck updated successfully".to_string(),
+                deck: Some(deck),
+            }),
+        ),
+        Err(e) => (
+            deck_error_status(&e),
+            Json(DeckResponse {
+                success: false,
+                message: e,
+                deck: None,
+            }),
+        ),
+    }
+}
+
+pub async fn delete_deck_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(deck_id): Path<String>,
+) -> (StatusCode, Json<DeckResponse>) {
+    let user = match authenticated_user(&state, &headers).await {
+        Ok(user) => user,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(DeckResponse { success: false, message: e, deck: None })),
+    };
+    let pool = state.db_pool.as_ref();
+
+    match decks::delete_deck(pool, &user.id, &deck_id).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(DeckResponse {
+                success: true,
+                message: "Deck deleted successfully".to_string(),
+                deck: None,
+            }),
+        ),
+        Err(e) => (
+            deck_error_status(&e),
+            Json(DeckResponse {
+                success: false,
+                message: e,
+                deck: None,
+            }),
+        ),
+    }
+}
@@ -4,6 +4,15 @@ mod handlers;
 mod users;
 mod upload;
 mod scryfall;
+mod decklist;
+mod decks;
+mod permissions;
+mod persistence;
+mod events;
+mod dice;
+mod tokens;
+mod sync;
+mod reaper;
 
 // Re-export websocket_new as websocket for compatibility
 use websocket_new as websocket;
@@ -17,7 +26,6 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
-use tracing_subscriber;
 use sqlx::postgres::PgPool;
 
 use game::GameManager;
@@ -100,6 +108,117 @@ async fn main() {
     .await
     .expect("Failed to create index");
 
+    // Scryfall ids and token flags, used to link cards to the tokens they create
+    sqlx::query("ALTER TABLE cards ADD COLUMN IF NOT EXISTS scryfall_id VARCHAR(64)")
+        .execute(&pool)
+        .await
+        .expect("Failed to add scryfall_id column");
+
+    sqlx::query("ALTER TABLE cards ADD COLUMN IF NOT EXISTS is_token BOOLEAN NOT NULL DEFAULT false")
+        .execute(&pool)
+        .await
+        .expect("Failed to add is_token column");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_cards_scryfall_id ON cards(scryfall_id)"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create index");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS card_tokens (
+            card_scryfall_id VARCHAR(64) NOT NULL,
+            token_scryfall_id VARCHAR(64) NOT NULL,
+            token_name VARCHAR(255) NOT NULL,
+            PRIMARY KEY (card_scryfall_id, token_scryfall_id)
+        )"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create card_tokens table");
+
+    // Create sessions table for login tokens
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            token VARCHAR(64) PRIMARY KEY,
+            user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            expires_at TIMESTAMP NOT NULL
+        )"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create sessions table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id)"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create index");
+
+    // Create decks tables
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS decks (
+            id UUID PRIMARY KEY,
+            user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            name VARCHAR(255) NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create decks table");
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_decks_user_id ON decks(user_id)"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create index");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS deck_cards (
+            deck_id UUID NOT NULL REFERENCES decks(id) ON DELETE CASCADE,
+            position INTEGER NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            set_code VARCHAR(10),
+            collector_number VARCHAR(20),
+            quantity INTEGER NOT NULL DEFAULT 1,
+            is_commander BOOLEAN NOT NULL DEFAULT false,
+            PRIMARY KEY (deck_id, position)
+        )"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create deck_cards table");
+
+    // Create game_sessions table for persisted games
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS game_sessions (
+            id VARCHAR(32) PRIMARY KEY,
+            state TEXT NOT NULL,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create game_sessions table");
+
+    // Create game_archive table for games expired after sitting idle
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS game_archive (
+            id VARCHAR(32) PRIMARY KEY,
+            state TEXT NOT NULL,
+            archived_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create game_archive table");
+
     // Sync Scryfall cards in background (doesn't need to be Send)
     tracing::info!("Starting Scryfall card sync...");
     let pool_clone = pool.clone();
@@ -139,11 +258,56 @@ async fn main() {
         });
     });
 
-    let game_manager = Arc::new(RwLock::new(GameManager::new()));
+    let game_id_length = std::env::var("GAME_ID_LENGTH")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(game::DEFAULT_GAME_ID_LENGTH);
+
+    // Restore games that were running before the last shutdown
+    let mut manager = GameManager::new().with_id_length(game_id_length);
+    match persistence::load_game_ids(&pool).await {
+        Ok(ids) => manager.reserve_ids(ids),
+        Err(e) => tracing::error!("{}", e),
+    }
+    match persistence::load_games(&pool).await {
+        Ok(sessions) => {
+            tracing::info!("Restored {} saved games", sessions.len());
+            for session in sessions {
+                manager.restore_game(session);
+            }
+        }
+        Err(e) => tracing::error!("{}", e),
+    }
+    let game_manager = Arc::new(RwLock::new(manager));
+    let pool = Arc::new(pool);
+
+    // Save changed games in the background
+    let save_interval_secs = std::env::var("GAME_SAVE_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(5);
+    tokio::spawn(persistence::run_save_loop(pool.clone(), game_manager.clone(), save_interval_secs));
+
+    // Expire games nobody has touched in a while. A timeout of 0 keeps them forever
+    let env_secs = |name: &str, default: u64| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(default)
+    };
+    let idle_policy = reaper::IdlePolicy {
+        idle_secs: env_secs("GAME_IDLE_TIMEOUT_SECS", 4 * 60 * 60),
+        warning_secs: env_secs("GAME_IDLE_WARNING_SECS", 5 * 60),
+    };
+    if idle_policy.idle_secs > 0 {
+        let reaper = reaper::Reaper::new(reaper::SystemClock, idle_policy);
+        let interval_secs = env_secs("GAME_REAPER_INTERVAL_SECS", 60).max(1);
+        tokio::spawn(reaper::run_reaper_loop(pool.clone(), game_manager.clone(), reaper, interval_secs));
+    }
 
     let state = AppState {
         game_manager,
-        db_pool: Arc::new(pool),
+        db_pool: pool,
     };
 
     let cors = CorsLayer::permissive();
@@ -151,14 +315,21 @@ async fn main() {
     // API routes
     let api_routes = Router::new()
         .route("/health", get(handlers::health_handler))
-        .route("/game/create", get(handlers::create_game_handler))
+        .route("/game/create", get(handlers::create_game_handler).post(handlers::create_game_handler))
+        .route("/games", get(handlers::list_games_handler))
+        .route("/game/:game_id/join", post(handlers::join_game_handler))
+        .route("/game/:game_id/events", get(handlers::game_events_handler))
         .route("/auth/register", post(handlers::register_handler))
         .route("/auth/login", post(handlers::login_handler))
+        .route("/auth/logout", post(handlers::logout_handler))
         .route("/auth/reset-password", post(handlers::reset_password_handler))
         .route("/cards/query", get(handlers::query_card_handler))
         .route("/cards/search", get(handlers::search_cards_handler))
+        .route("/decks", get(handlers::list_decks_handler).post(handlers::create_deck_handler))
+        .route("/decks/:deck_id", get(handlers::get_deck_handler).put(handlers::update_deck_handler).delete(handlers::delete_deck_handler))
         .route("/upload", post(upload::upload_handler))
-        .route("/ws/:game_id/:player_id/:player_name", get(websocket::ws_handler))
+        .route("/ws/:game_id", get(websocket::ws_handler))
+        .route("/ws/:game_id/spectate", get(websocket::spectate_handler))
         .with_state(state.clone());
 
     // Serve card images from /GameTableData
@@ -0,0 +1,162 @@
+use crate::game::GameSession;
+use crate::websocket_new::Message;
+
+/// Returns the player a message acts on, if it names one explicitly.
+/// Messages without a `player_id` always act on the sender's own board.
+fn target_player(msg: &Message) -> Option<&str> {
+    match msg {
+        Message::UpdateLife { player_id, .. }
+        | Message::UpdateCounter { player_id, .. }
+        | Message::SetPlayerName { player_id, .. }
+        | Message::DiceRoll { player_id, .. }
+        | Message::LoadLibrary { player_id, .. }
+        | Message::LoadDeck { player_id, .. }
+        | Message::ShuffleLibrary { player_id }
+        | Message::TapCard { player_id, .. }
+        | Message::FlipCard { player_id, .. }
+        | Message::Copy { player_id, .. }
+        | Message::UpdateCommanderDamage { player_id, .. }
+        | Message::Reinstate { player_id }
+        | Message::AttachCard { player_id, .. }
+        | Message::DetachCard { player_id, .. }
+        | Message::UpdateCardCounter { player_id, .. }
+        | Message::UntapAll { player_id }
+        | Message::MoveCardOnBattlefield { player_id, .. }
+        | Message::RevealCard { player_id, .. }
+        | Message::Scry { player_id, .. }
+        | Message::Surveil { player_id, .. }
+        | Message::ScryComplete { player_id, .. }
+        | Message::SurveilComplete { player_id, .. }
+        | Message::ManifestCard { player_id, .. }
+        | Message::SpawnCard { player_id, .. }
+        | Message::FlipCardFace { player_id, .. }
+        | Message::SearchLibrary { player_id }
+        | Message::TutorCard { player_id, .. }
+        | Message::PutInLibrary { player_id, .. }
+        | Message::ExileTopFaceDown { player_id, .. }
+        | Message::RevealUntil { player_id, .. }
+        | Message::ListRelatedTokens { player_id, .. }
+        | Message::CreateRelatedToken { player_id, .. } => Some(player_id),
+        _ => None,
+    }
+}
+
+/// Rules for what one player may change on another player's board.
+/// Life, damage and eliminations are shared bookkeeping at a real table, so
+/// anyone may adjust them; zones, library order and hands stay with their owner.
+fn allowed_on_other_player(msg: &Message) -> bool {
+    match msg {
+        Message::UpdateLife { .. } | Message::UpdateCommanderDamage { .. } => true,
+        // Fixing a mistaken elimination is table bookkeeping too
+        Message::Reinstate { .. } => true,
+        Message::UpdateCounter { counter_type, .. } => counter_type == "poison",
+        _ => false,
+    }
+}
+
+/// Messages that steer the whole game rather than one board. At a real
+/// table these are up to the player whose turn it is, or whoever runs the game.
+fn is_game_wide(msg: &Message) -> bool {
+    matches!(
+        msg,
+        Message::NextTurn {}
+            | Message::AdvanceStep {}
+            | Message::UndoTurn {}
+            | Message::RestartGame {}
+            | Message::ReorderSeats { .. }
+            | Message::RandomizeStartingPlayer {}
+            | Message::SetTurnAutomation { .. }
+            | Message::ResolveStack {}
+            | Message::CounterSpell { .. }
+            | Message::ReorderStack { .. }
+            | Message::StartMulligans { .. }
+    )
+}
+
+/// Checks whether `actor_id` may apply `msg` to `game`. Returns the reason on rejection.
+pub fn authorize(actor_id: &str, msg: &Message, game: &GameSession) -> Result<(), String> {
+    if is_game_wide(msg) {
+        let is_active = game.active_player_id() == Some(actor_id);
+        let is_host = game.settings.host_id.as_deref() == Some(actor_id);
+        if !is_active && !is_host {
+            return Err("Only the active player or the host can do that".to_string());
+        }
+    }
+    match target_player(msg) {
+        Some(target) if target != actor_id && !allowed_on_other_player(msg) => {
+            Err("You can only make that change to your own board".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Player;
+
+    /// A game where "a" is the active player and "host" runs the table.
+    fn game() -> GameSession {
+        let mut game = GameSession::new("g".to_string());
+        for (i, id) in ["a", "b"].into_iter().enumerate() {
+            game.add_player(Player::new(id.to_string(), id.to_string(), i));
+        }
+        game.settings.host_id = Some("host".to_string());
+        game
+    }
+
+    fn tap(player_id: &str) -> Message {
+        Message::TapCard { player_id: player_id.to_string(), card_id: "x".to_string() }
+    }
+
+    fn counter(player_id: &str, counter_type: &str) -> Message {
+        Message::UpdateCounter {
+            player_id: player_id.to_string(),
+            counter_type: counter_type.to_string(),
+            delta: 1,
+        }
+    }
+
+    #[test]
+    fn players_may_change_their_own_board() {
+        let game = game();
+        assert!(authorize("b", &tap("b"), &game).is_ok());
+        assert!(authorize("b", &counter("b", "energy"), &game).is_ok());
+        assert!(authorize("b", &Message::ShuffleLibrary { player_id: "b".to_string() }, &game).is_ok());
+    }
+
+    #[test]
+    fn only_table_bookkeeping_reaches_other_boards() {
+        let game = game();
+        assert!(authorize("b", &Message::UpdateLife { player_id: "a".to_string(), delta: -2 }, &game).is_ok());
+        assert!(authorize("b", &counter("a", "poison"), &game).is_ok());
+        assert!(authorize("b", &Message::Reinstate { player_id: "a".to_string() }, &game).is_ok());
+
+        assert!(authorize("b", &tap("a"), &game).is_err());
+        assert!(authorize("b", &counter("a", "energy"), &game).is_err());
+        assert!(authorize("b", &Message::ShuffleLibrary { player_id: "a".to_string() }, &game).is_err());
+    }
+
+    #[test]
+    fn game_wide_messages_need_the_active_player_or_host() {
+        let game = game();
+        let game_wide = [
+            Message::NextTurn {},
+            Message::AdvanceStep {},
+            Message::UndoTurn {},
+            Message::RestartGame {},
+            Message::ReorderSeats { seats: vec!["b".to_string(), "a".to_string()] },
+            Message::RandomizeStartingPlayer {},
+            Message::SetTurnAutomation { auto_untap: true, auto_draw: false },
+            Message::ResolveStack {},
+            Message::CounterSpell { card_id: "x".to_string() },
+            Message::ReorderStack { card_ids: vec![] },
+            Message::StartMulligans { free_first: None },
+        ];
+        for msg in &game_wide {
+            assert!(authorize("a", msg, &game).is_ok(), "{:?} from the active player", msg);
+            assert!(authorize("host", msg, &game).is_ok(), "{:?} from the host", msg);
+            assert!(authorize("b", msg, &game).is_err(), "{:?} from another player", msg);
+        }
+    }
+}
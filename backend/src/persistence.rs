@@ -0,0 +1,107 @@
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::game::{GameManager, GameSession};
+
+pub async fn save_game(pool: &PgPool, game_id: &str, state_json: &str) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO game_sessions (id, state, updated_at)
+         VALUES ($1, $2, CURRENT_TIMESTAMP)
+         ON CONFLICT (id) DO UPDATE SET state = EXCLUDED.state, updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(game_id)
+    .bind(state_json)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to save game {}: {}", game_id, e))?;
+    Ok(())
+}
+
+pub async fn delete_game(pool: &PgPool, game_id: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM game_sessions WHERE id = $1")
+        .bind(game_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to delete game {}: {}", game_id, e))?;
+    Ok(())
+}
+
+/// Loads every saved session. Snapshots that no longer deserialize are skipped.
+pub async fn load_games(pool: &PgPool) -> Result<Vec<GameSession>, String> {
+    let rows = sqlx::query_as::<_, (String, String)>("SELECT id, state FROM game_sessions")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load games: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, state)| match serde_json::from_str::<GameSession>(&state) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable saved game {}: {}", id, e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Moves a game's snapshot from the running games to the archive.
+pub async fn archive_game(pool: &PgPool, game_id: &str, state_json: &str) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to archive game {}: {}", game_id, e))?;
+    sqlx::query(
+        "INSERT INTO game_archive (id, state, archived_at)
+         VALUES ($1, $2, CURRENT_TIMESTAMP)
+         ON CONFLICT (id) DO UPDATE SET state = EXCLUDED.state, archived_at = CURRENT_TIMESTAMP",
+    )
+    .bind(game_id)
+    .bind(state_json)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to archive game {}: {}", game_id, e))?;
+    sqlx::query("DELETE FROM game_sessions WHERE id = $1")
+        .bind(game_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to archive game {}: {}", game_id, e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to archive game {}: {}", game_id, e))
+}
+
+/// Ids of every saved and archived session, including ones that no longer load.
+pub async fn load_game_ids(pool: &PgPool) -> Result<Vec<String>, String> {
+    sqlx::query_scalar::<_, String>("SELECT id FROM game_sessions UNION SELECT id FROM game_archive")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load game ids: {}", e))
+}
+
+/// Writes changed and deleted games to the database.
+pub async fn flush(pool: &PgPool, game_manager: &Arc<RwLock<GameManager>>) {
+    // Serialize under the lock, write without it
+    let (saved, deleted) = game_manager.write().await.take_pending_saves();
+
+    for (game_id, state_json) in &saved {
+        if let Err(e) = save_game(pool, game_id, state_json).await {
+            tracing::error!("{}", e);
+        }
+    }
+    for game_id in &deleted {
+        if let Err(e) = delete_game(pool, game_id).await {
+            tracing::error!("{}", e);
+        }
+    }
+}
+
+/// Periodically saves changed games so they survive a server restart.
+pub async fn run_save_loop(pool: Arc<PgPool>, game_manager: Arc<RwLock<GameManager>>, interval_secs: u64) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        flush(&pool, &game_manager).await;
+    }
+}
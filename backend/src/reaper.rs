@@ -0,0 +1,187 @@
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::game::{unix_now, GameManager, GameSession};
+use crate::persistence;
+
+/// Where the reaper gets the time from, so tests can move it by hand.
+pub trait Clock: Send + Sync + 'static {
+    /// Seconds since the Unix epoch.
+    fn now(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        unix_now()
+    }
+}
+
+/// How long a game may sit without a state change.
+#[derive(Debug, Clone, Copy)]
+pub struct IdlePolicy {
+    pub idle_secs: u64,
+    /// How long before expiry connected players are warned. A game is never
+    /// expired less than this long after its warning went out.
+    pub warning_secs: u64,
+}
+
+pub struct Reaper<C: Clock> {
+    clock: C,
+    policy: IdlePolicy,
+}
+
+impl<C: Clock> Reaper<C> {
+    pub fn new(clock: C, policy: IdlePolicy) -> Self {
+        Self { clock, policy }
+    }
+
+    /// Warns players in games that are about to expire and removes the ones
+    /// whose time is up. Returns the removed games so they can be archived.
+    pub fn sweep(&self, gm: &mut GameManager) -> Vec<GameSession> {
+        let now = self.clock.now();
+        let IdlePolicy { idle_secs, warning_secs } = self.policy;
+        let mut expired = Vec::new();
+
+        for game in gm.games_mut() {
+            let idle_deadline = game.last_activity + idle_secs;
+            match game.idle_warned_at {
+                None if now + warning_secs >= idle_deadline => {
+                    game.idle_warned_at = Some(now);
+                    let expires_in = idle_deadline.max(now + warning_secs) - now;
+                    game.broadcast_message(serde_json::json!({
+                        "IdleWarning": {
+                            "expires_in_secs": expires_in
+                        }
+                    }).to_string());
+                }
+                Some(warned_at) if now >= idle_deadline.max(warned_at + warning_secs) => {
+                    expired.push(game.id.clone());
+                }
+                _ => {}
+            }
+        }
+
+        expired
+            .iter()
+            .filter_map(|game_id| gm.expire_game(game_id))
+            .inspect(|game| {
+                game.broadcast_message(serde_json::json!({
+                    "GameExpired": {
+                        "reason": "The game was closed after being idle for too long"
+                    }
+                }).to_string());
+            })
+            .collect()
+    }
+}
+
+/// Periodically expires idle games and archives them.
+pub async fn run_reaper_loop<C: Clock>(
+    pool: Arc<PgPool>,
+    game_manager: Arc<RwLock<GameManager>>,
+    reaper: Reaper<C>,
+    interval_secs: u64,
+) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        // Sweep under the lock, archive without it
+        let expired = reaper.sweep(&mut *game_manager.write().await);
+
+        for game in expired {
+            tracing::info!("Expiring idle game {}", game.id);
+            match serde_json::to_string(&game) {
+                Ok(json) => {
+                    if let Err(e) = persistence::archive_game(&pool, &game.id, &json).await {
+                        tracing::error!("{}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to serialize game {}: {}", game.id, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameSettings;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[derive(Clone)]
+    struct ManualClock(Arc<AtomicU64>);
+
+    impl ManualClock {
+        fn set(&self, now: u64) {
+            self.0.store(now, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> u64 {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    fn setup() -> (GameManager, String, ManualClock, Reaper<ManualClock>) {
+        let mut gm = GameManager::new();
+        let game_id = gm.create_game(GameSettings::default());
+        gm.get_game_mut(&game_id).unwrap().last_activity = 1000;
+        let clock = ManualClock(Arc::new(AtomicU64::new(1000)));
+        let reaper = Reaper::new(clock.clone(), IdlePolicy { idle_secs: 600, warning_secs: 60 });
+        (gm, game_id, clock, reaper)
+    }
+
+    #[test]
+    fn idle_games_are_warned_then_expired() {
+        let (mut gm, game_id, clock, reaper) = setup();
+
+        clock.set(1500);
+        assert!(reaper.sweep(&mut gm).is_empty());
+        assert_eq!(gm.get_game(&game_id).unwrap().idle_warned_at, None);
+
+        clock.set(1550);
+        assert!(reaper.sweep(&mut gm).is_empty());
+        assert_eq!(gm.get_game(&game_id).unwrap().idle_warned_at, Some(1550));
+
+        // The warning went out late, so players still get its full minute
+        clock.set(1600);
+        assert!(reaper.sweep(&mut gm).is_empty());
+        clock.set(1610);
+        let expired = reaper.sweep(&mut gm);
+        assert_eq!(expired.len(), 1);
+        assert!(gm.get_game(&game_id).is_none());
+    }
+
+    #[test]
+    fn games_are_never_expired_without_a_warning() {
+        let (mut gm, game_id, clock, reaper) = setup();
+
+        // Long past the deadline, but nobody has been warned yet
+        clock.set(5000);
+        assert!(reaper.sweep(&mut gm).is_empty());
+        clock.set(5059);
+        assert!(reaper.sweep(&mut gm).is_empty());
+        clock.set(5060);
+        assert_eq!(reaper.sweep(&mut gm)[0].id, game_id);
+    }
+
+    #[test]
+    fn activity_after_a_warning_keeps_the_game() {
+        let (mut gm, game_id, clock, reaper) = setup();
+
+        clock.set(1550);
+        reaper.sweep(&mut gm);
+        let game = gm.get_game_mut(&game_id).unwrap();
+        game.broadcast_state();
+        assert_eq!(game.idle_warned_at, None);
+        game.last_activity = 1580;
+
+        clock.set(1600);
+        assert!(reaper.sweep(&mut gm).is_empty());
+        assert!(gm.get_game(&game_id).is_some());
+    }
+}
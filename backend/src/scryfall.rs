@@ -19,8 +19,18 @@ pub struct CardFace {
     pub image_uris: Option<CardImageUris>,
 }
 
+/// An entry in a card's `all_parts`, e.g. the tokens it creates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedCard {
+    pub id: String,
+    pub component: String,
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScryfallCard {
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub collector_number: String,
     pub set: String,
@@ -28,6 +38,22 @@ pub struct ScryfallCard {
     pub layout: String,
     pub image_uris: Option<CardImageUris>,
     pub card_faces: Option<Vec<CardFace>>,
+    #[serde(default)]
+    pub all_parts: Option<Vec<RelatedCard>>,
+}
+
+impl ScryfallCard {
+    pub fn is_token(&self) -> bool {
+        matches!(self.layout.as_str(), "token" | "double_faced_token" | "emblem")
+    }
+
+    /// Tokens this card makes, excluding the card itself when it is a token.
+    pub fn related_tokens(&self) -> impl Iterator<Item = &RelatedCard> {
+        self.all_parts
+            .iter()
+            .flatten()
+            .filter(move |part| part.component == "token" && part.id != self.id)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,19 +103,42 @@ pub async fn fetch_cards_from_scryfall(set_code: &str) -> Result<Vec<ScryfallCar
             .send()
             .await?;
 
+        // Scryfall answers 404 when a search has no results, e.g. a set without tokens
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            break;
+        }
+
         let body: ScryfallResponse = response.json().await?;
         cards.extend(body.data);
 
-        if body.has_more && body.next_page.is_some() {
-            url = body.next_page.unwrap();
-        } else {
-            break;
+        match body.next_page {
+            Some(next_page) if body.has_more => url = next_page,
+            _ => break,
         }
     }
 
     Ok(cards)
 }
 
+/// Fetches a single printing by its Scryfall id.
+pub async fn fetch_card_by_id(id: &str) -> Result<ScryfallCard, Box<dyn std::error::Error>> {
+    rate_limit().await;
+
+    let url = format!("https://api.scryfall.com/cards/{}", id);
+    tracing::info!("Fetching card from Scryfall: {}", url);
+
+    let card = reqwest::Client::new()
+        .get(&url)
+        .header("User-Agent", "GameTable/1.0")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(card)
+}
+
 pub async fn insert_cards_into_db(
     pool: &PgPool,
     cards: &[ScryfallCard],
@@ -109,6 +158,19 @@ pub async fn insert_cards_into_db(
         .await?;
 
         if exists.0 {
+            // Rows synced before ids were stored still need them for token lookups
+            sqlx::query(
+                "UPDATE cards SET scryfall_id = $1, is_token = $2
+                 WHERE name = $3 AND collector_number = $4 AND set_code = $5 AND scryfall_id IS NULL"
+            )
+            .bind(&card.id)
+            .bind(card.is_token())
+            .bind(&card.name)
+            .bind(&card.collector_number)
+            .bind(&card.set)
+            .execute(pool)
+            .await?;
+
             tracing::debug!("Card already exists: {} #{} ({})", card.name, card.collector_number, card.set);
             skipped_count += 1;
             continue;
@@ -121,8 +183,8 @@ pub async fn insert_cards_into_db(
 
         let result = sqlx::query(
             r#"
-            INSERT INTO cards (name, collector_number, set_code, set_name, is_two_sided)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO cards (name, collector_number, set_code, set_name, is_two_sided, scryfall_id, is_token)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#
         )
         .bind(&card.name)
@@ -130,6 +192,8 @@ pub async fn insert_cards_into_db(
         .bind(&card.set)
         .bind(&card.set_name)
         .bind(is_two_sided)
+        .bind(&card.id)
+        .bind(card.is_token())
         .execute(pool)
         .await?;
 
@@ -146,6 +210,72 @@ pub async fn insert_cards_into_db(
     Ok(inserted_count)
 }
 
+/// Records which tokens each card creates.
+pub async fn insert_related_tokens(
+    pool: &PgPool,
+    cards: &[ScryfallCard],
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut inserted_count = 0;
+
+    for card in cards {
+        for token in card.related_tokens() {
+            let result = sqlx::query(
+                "INSERT INTO card_tokens (card_scryfall_id, token_scryfall_id, token_name)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT DO NOTHING"
+            )
+            .bind(&card.id)
+            .bind(&token.id)
+            .bind(&token.name)
+            .execute(pool)
+            .await?;
+
+            inserted_count += result.rows_affected() as usize;
+        }
+    }
+
+    Ok(inserted_count)
+}
+
+/// Fetches token printings that cards point at but that aren't stored yet.
+/// Reprinted cards often reference a token from an older set's token list.
+pub async fn fetch_missing_tokens(
+    pool: &PgPool,
+    cards: &[ScryfallCard],
+) -> Result<Vec<ScryfallCard>, Box<dyn std::error::Error>> {
+    let mut token_ids: Vec<&str> = cards
+        .iter()
+        .flat_map(|card| card.related_tokens())
+        .map(|token| token.id.as_str())
+        .collect();
+    token_ids.sort_unstable();
+    token_ids.dedup();
+
+    let mut missing = Vec::new();
+    for token_id in token_ids {
+        if cards.iter().any(|card| card.id == token_id) {
+            continue;
+        }
+
+        let exists: (bool,) = sqlx::query_as(
+            "SELECT EXISTS(SELECT 1 FROM cards WHERE scryfall_id = $1)"
+        )
+        .bind(token_id)
+        .fetch_one(pool)
+        .await?;
+        if exists.0 {
+            continue;
+        }
+
+        match fetch_card_by_id(token_id).await {
+            Ok(token) => missing.push(token),
+            Err(e) => tracing::warn!("Failed to fetch token {}: {}", token_id, e),
+        }
+    }
+
+    Ok(missing)
+}
+
 pub async fn download_card_images(
     cards: &[ScryfallCard],
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -167,10 +297,10 @@ pub async fn download_card_images(
         // Download back image for dual-faced cards
         let is_two_sided = card.layout == "transform" 
             || card.layout == "modal_dfc" 
-            || card.layout == "meld";
+            || card.layout == "meld"
+            || card.layout == "double_faced_token";
 
-        if is_two_sided && card.card_faces.is_some() {
-            let card_faces = card.card_faces.as_ref().unwrap();
+        if let (true, Some(card_faces)) = (is_two_sided, card.card_faces.as_ref()) {
             if card_faces.len() > 1 {
                 if let Some(back_face) = card_faces.get(1) {
                     if let Some(back_uris) = &back_face.image_uris {
@@ -228,8 +358,27 @@ pub async fn sync_all_sets(pool: &PgPool, set_codes: &[String]) -> Result<(), Bo
         tracing::info!("Syncing set: {}", set_code);
         
         match fetch_cards_from_scryfall(set_code).await {
-            Ok(cards) => {
+            Ok(mut cards) => {
                 tracing::info!("Fetched {} cards from set {}", cards.len(), set_code);
+
+                // Scryfall keeps a set's tokens in a separate "t" set
+                let token_set = format!("t{}", set_code);
+                match fetch_cards_from_scryfall(&token_set).await {
+                    Ok(tokens) => {
+                        tracing::info!("Fetched {} tokens from set {}", tokens.len(), token_set);
+                        cards.extend(tokens);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch tokens for set {}: {}", set_code, e);
+                    }
+                }
+
+                match fetch_missing_tokens(pool, &cards).await {
+                    Ok(tokens) => cards.extend(tokens),
+                    Err(e) => {
+                        tracing::error!("Failed to look up related tokens for set {}: {}", set_code, e);
+                    }
+                }
                 
                 match insert_cards_into_db(pool, &cards).await {
                     Ok(inserted) => {
@@ -244,6 +393,10 @@ pub async fn sync_all_sets(pool: &PgPool, set_codes: &[String]) -> Result<(), Bo
                     }
                 }
 
+                if let Err(e) = insert_related_tokens(pool, &cards).await {
+                    tracing::error!("Failed to store related tokens for set {}: {}", set_code, e);
+                }
+
                 match download_card_images(&cards).await {
                     Ok(_) => {
                         tracing::info!("Processed images for set {} (skipped existing files)", set_code);
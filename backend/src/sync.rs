@@ -0,0 +1,148 @@
+use serde_json::{Map, Value};
+
+use crate::game::GameSession;
+
+/// Builds a JSON Merge Patch (RFC 7386) that turns `old` into `new`.
+/// Objects are diffed key by key, anything else is replaced whole and removed
+/// keys are sent as `null`. Returns `None` when nothing changed.
+pub fn merge_patch(old: &Value, new: &Value) -> Option<Value> {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut patch = Map::new();
+            for (key, new_value) in new {
+                match old.get(key) {
+                    Some(old_value) => {
+                        if let Some(changed) = merge_patch(old_value, new_value) {
+                            patch.insert(key.clone(), changed);
+                        }
+                    }
+                    None => {
+                        patch.insert(key.clone(), new_value.clone());
+                    }
+                }
+            }
+            for key in old.keys().filter(|key| !new.contains_key(*key)) {
+                patch.insert(key.clone(), Value::Null);
+            }
+            (!patch.is_empty()).then_some(Value::Object(patch))
+        }
+        _ if old == new => None,
+        _ => Some(new.clone()),
+    }
+}
+
+/// Tracks what one connection has been sent so each state change can go out
+/// as a patch against it.
+#[derive(Default)]
+pub struct ViewerSync {
+    version: u64,
+    view: Option<Value>,
+}
+
+impl ViewerSync {
+    fn view_of(game: &GameSession, viewer_id: &str) -> Value {
+        serde_json::to_value(game.view_for(viewer_id)).unwrap_or_default()
+    }
+
+    fn join_order(game: &GameSession, viewer_id: &str) -> usize {
+        game.players.get(viewer_id).map(|p| p.join_order).unwrap_or(0)
+    }
+
+    /// The full `GameState` message. Sent on connect and whenever the client
+    /// asks for a resync after noticing a gap.
+    pub fn full(&mut self, game: &GameSession, viewer_id: &str) -> String {
+        let view = Self::view_of(game, viewer_id);
+        let msg = serde_json::json!({
+            "GameState": {
+                "state": view,
+                "version": game.version,
+                "player_id": viewer_id,
+                "player_join_order": Self::join_order(game, viewer_id)
+            }
+        }).to_string();
+        self.version = game.version;
+        self.view = Some(view);
+        msg
+    }
+
+    /// A `StatePatch` message taking the client from the last version it was
+    /// sent to `game.version`. Returns `None` for snapshots the client already
+    /// has or changes this viewer can't see.
+    pub fn update(&mut self, game: &GameSession, viewer_id: &str) -> Option<String> {
+        let Some(old) = &self.view else {
+            return Some(self.full(game, viewer_id));
+        };
+        if game.version <= self.version {
+            return None;
+        }
+
+        let view = Self::view_of(game, viewer_id);
+        // Nothing this viewer can see changed. The client's version stays put
+        // so the next patch doesn't look like it skipped one
+        let patch = merge_patch(old, &view)?;
+        let base_version = self.version;
+        self.version = game.version;
+        self.view = Some(view);
+
+        Some(serde_json::json!({
+            "StatePatch": {
+                "base_version": base_version,
+                "version": game.version,
+                "patch": patch,
+                "player_join_order": Self::join_order(game, viewer_id)
+            }
+        }).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// What the client does with a patch.
+    fn apply(target: &mut Value, patch: &Value) {
+        match (target.as_object_mut(), patch.as_object()) {
+            (Some(target), Some(patch)) => {
+                for (key, value) in patch {
+                    if value.is_null() {
+                        target.remove(key);
+                    } else {
+                        apply(target.entry(key.clone()).or_insert(Value::Null), value);
+                    }
+                }
+            }
+            _ => *target = patch.clone(),
+        }
+    }
+
+    #[test]
+    fn patches_only_carry_what_changed() {
+        let old = json!({"turn": 1, "players": {"a": {"life": 40, "hand": [1, 2]}, "b": {"life": 40}}, "gone": true});
+        let new = json!({"turn": 1, "players": {"a": {"life": 38, "hand": [1, 2]}, "b": {"life": 40}}, "added": [3]});
+
+        let patch = merge_patch(&old, &new).unwrap();
+        assert_eq!(patch, json!({"players": {"a": {"life": 38}}, "gone": null, "added": [3]}));
+        assert_eq!(merge_patch(&new, &new), None);
+
+        let mut client = old.clone();
+        apply(&mut client, &patch);
+        assert_eq!(client, new);
+    }
+
+    #[test]
+    fn viewers_get_a_full_state_then_patches() {
+        let mut game = GameSession::new("g".to_string());
+        game.add_player(crate::game::Player::new("a".to_string(), "A".to_string(), 0));
+        let mut sync = ViewerSync::default();
+
+        assert!(sync.update(&game, "a").unwrap().contains("GameState"));
+        assert_eq!(sync.update(&game, "a"), None);
+
+        game.update_life("a", -2).unwrap();
+        game.version += 1;
+        let msg: Value = serde_json::from_str(&sync.update(&game, "a").unwrap()).unwrap();
+        assert_eq!(msg["StatePatch"]["base_version"], json!(game.version - 1));
+        assert_eq!(msg["StatePatch"]["patch"]["players"]["a"]["life"], json!(38));
+    }
+}
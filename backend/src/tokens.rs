@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::game::Card;
+
+/// Most tokens a single `CreateRelatedToken` may put onto the battlefield.
+pub const MAX_TOKENS_PER_REQUEST: usize = 50;
+
+/// A token printing that a card creates, as recorded by the Scryfall sync.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RelatedToken {
+    pub name: String,
+    pub set_code: String,
+    pub collector_number: String,
+    pub is_two_sided: bool,
+}
+
+impl RelatedToken {
+    pub fn to_card(&self, position_x: f32, position_y: f32) -> Card {
+        Card {
+            id: format!("token_{}", Uuid::new_v4()),
+            name: self.name.clone(),
+            is_tapped: false,
+            is_flipped: false,
+            is_commander: false,
+            is_token: true,
+            is_two_sided: self.is_two_sided,
+            is_back_face: false,
+            set_code: Some(self.set_code.clone()),
+            collector_number: Some(self.collector_number.clone()),
+            position_x,
+            position_y,
+            is_revealed: false,
+            counters: Default::default(),
+            attached_to: None,
+            is_aura: false,
+            owner_id: None,
+        }
+    }
+}
+
+/// Looks up the tokens made by the printing `set_code`/`collector_number`.
+pub async fn related_tokens(
+    pool: &PgPool,
+    set_code: &str,
+    collector_number: &str,
+) -> Result<Vec<RelatedToken>, String> {
+    let rows = sqlx::query_as::<_, (String, String, String, bool)>(
+        "SELECT t.name, t.set_code, t.collector_number, t.is_two_sided
+         FROM cards c
+         JOIN card_tokens ct ON ct.card_scryfall_id = c.scryfall_id
+         JOIN cards t ON t.scryfall_id = ct.token_scryfall_id
+         WHERE c.set_code = $1 AND c.collector_number = $2
+         ORDER BY t.name, t.set_code, t.collector_number"
+    )
+    .bind(set_code)
+    .bind(collector_number)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(name, set_code, collector_number, is_two_sided)| RelatedToken {
+            name,
+            set_code,
+            collector_number,
+            is_two_sided,
+        })
+        .collect())
+}
+
+/// Picks the token to create. A name is only needed when the card makes
+/// more than one kind of token.
+pub fn choose_token(tokens: Vec<RelatedToken>, token_name: Option<&str>) -> Result<RelatedToken, String> {
+    if tokens.is_empty() {
+        return Err("That card doesn't make any known tokens".to_string());
+    }
+
+    match token_name {
+        Some(wanted) => tokens
+            .into_iter()
+            .find(|t| t.name.eq_ignore_ascii_case(wanted.trim()))
+            .ok_or(format!("That card doesn't make a {} token", wanted)),
+        None if tokens.len() == 1 => Ok(tokens.into_iter().next().unwrap()),
+        None => {
+            let mut names: Vec<&str> = tokens.iter().map(|t| t.name.as_str()).collect();
+            names.dedup();
+            Err(format!("That card makes several tokens, pick one of: {}", names.join(", ")))
+        }
+    }
+}
@@ -27,12 +27,23 @@ pub struct AuthResponse {
     pub success: bool,
     pub message: String,
     pub user: Option<User>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct LogoutRequest {
+    pub token: String,
+}
+
+/// How long a login session stays valid.
+const SESSION_DURATION_DAYS: i32 = 7;
+
 #[derive(Debug, Deserialize)]
 pub struct ResetPasswordRequest {
     pub username: String,
     pub new_password: String,
+    pub token: String,
 }
 
 pub async fn create_user(
@@ -138,6 +149,60 @@ pub async fn reset_password(
     }
 }
 
+/// Creates a login session for the user and returns its token.
+pub async fn create_session(pool: &PgPool, user_id: &str) -> Result<String, String> {
+    let user_uuid = Uuid::parse_str(user_id)
+        .map_err(|_| format!("Invalid user id: {}", user_id))?;
+
+    // 256 bits of randomness from two v4 UUIDs
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+    sqlx::query(
+        "INSERT INTO sessions (token, user_id, expires_at)
+         VALUES ($1, $2, CURRENT_TIMESTAMP + make_interval(days => $3))",
+    )
+    .bind(&token)
+    .bind(user_uuid)
+    .bind(SESSION_DURATION_DAYS)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create session: {}", e))?;
+
+    Ok(token)
+}
+
+/// Looks up the user owning a valid, unexpired session token.
+pub async fn user_for_token(pool: &PgPool, token: &str) -> Result<User, String> {
+    let row = sqlx::query(
+        "SELECT u.id, u.username FROM sessions s
+         JOIN users u ON u.id = s.user_id
+         WHERE s.token = $1 AND s.expires_at > CURRENT_TIMESTAMP",
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?
+    .ok_or("Invalid or expired session")?;
+
+    let user_id: Uuid = row.get("id");
+    Ok(User {
+        id: user_id.to_string(),
+        username: row.get("username"),
+        profile_picture_url: None,
+    })
+}
+
+pub async fn delete_session(pool: &PgPool, token: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM sessions WHERE token = $1")
+        .bind(token)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    Ok(())
+}
+
+// Not wired up to anything yet
+#[allow(dead_code)]
 pub async fn is_admin(username: &str) -> Result<bool, String> {
     use std::fs;
     use std::path::Path;
@@ -1,14 +1,26 @@
 use axum::{
-    extract::{Path, State, ws::{WebSocket, WebSocketUpgrade}},
-    response::IntoResponse,
+    extract::{Path, Query, State, ws::{WebSocket, WebSocketUpgrade}},
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use std::sync::Arc;
-use tokio::sync::{RwLock, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock, Mutex, OwnedMutexGuard};
+use tokio::time::Instant;
 use uuid::Uuid;
 
-use crate::game::{GameManager, Player, Zone, Card};
+use crate::decklist::{self, DecklistError};
+use crate::decks;
+use crate::dice;
+use crate::events::{self, GameEvent};
+use crate::permissions;
+use crate::sync::ViewerSync;
+use crate::tokens::{self, RelatedToken};
+use crate::users;
+use crate::game::{Broadcast, GameManager, GameSession, Player, Zone, Card, UndoOutcome, TurnAutomation, EliminationReason, SPECTATOR_VIEWER};
 use crate::AppState;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,9 +32,11 @@ pub enum Message {
     #[serde(rename = "SetPlayerName")]
     SetPlayerName { player_id: String, name: String },
     #[serde(rename = "DiceRoll")]
-    DiceRoll { player_id: String, roll_type: String, result: String },
+    DiceRoll { player_id: String, roll_type: String },
     #[serde(rename = "LoadLibrary")]
-    LoadLibrary { player_id: String, card_count: usize, card_type: String },
+    LoadLibrary { player_id: String, card_count: usize, card_type: String, #[serde(default, skip_serializing_if = "Option::is_none")] decklist: Option<String> },
+    #[serde(rename = "LoadDeck")]
+    LoadDeck { player_id: String, deck_id: String },
     #[serde(rename = "ShuffleLibrary")]
     ShuffleLibrary { player_id: String },
     #[serde(rename = "MoveCard")]
@@ -47,6 +61,54 @@ pub enum Message {
     NextTurn {},
     #[serde(rename = "UndoTurn")]
     UndoTurn {},
+    #[serde(rename = "Concede")]
+    Concede {},
+    #[serde(rename = "Reinstate")]
+    Reinstate { player_id: String },
+    #[serde(rename = "UpdateCommanderDamage")]
+    UpdateCommanderDamage { player_id: String, commander_id: String, delta: i32 },
+    #[serde(rename = "CastSpell")]
+    CastSpell { card_id: String, from_zone: String, #[serde(default)] is_permanent: bool },
+    #[serde(rename = "ResolveStack")]
+    ResolveStack {},
+    #[serde(rename = "CounterSpell")]
+    CounterSpell { card_id: String },
+    #[serde(rename = "ReorderStack")]
+    ReorderStack { card_ids: Vec<String> },
+    #[serde(rename = "SpawnSharedCard")]
+    SpawnSharedCard { set_code: String, collector_number: String, card_name: String },
+    #[serde(rename = "MoveSharedCard")]
+    MoveSharedCard { card_id: String, x: f32, y: f32 },
+    #[serde(rename = "RemoveSharedCard")]
+    RemoveSharedCard { card_id: String },
+    #[serde(rename = "GainControl")]
+    GainControl { card_id: String, controller_id: String },
+    #[serde(rename = "ReturnControl")]
+    ReturnControl { card_id: String },
+    #[serde(rename = "AttachCard")]
+    AttachCard { player_id: String, card_id: String, host_id: String, #[serde(default)] is_aura: bool },
+    #[serde(rename = "DetachCard")]
+    DetachCard { player_id: String, card_id: String },
+    #[serde(rename = "UpdateCardCounter")]
+    UpdateCardCounter { player_id: String, card_id: String, counter_type: String, delta: i32 },
+    #[serde(rename = "AdvanceStep")]
+    AdvanceStep {},
+    #[serde(rename = "SetTurnAutomation")]
+    SetTurnAutomation { auto_untap: bool, auto_draw: bool },
+    #[serde(rename = "RandomizeStartingPlayer")]
+    RandomizeStartingPlayer {},
+    #[serde(rename = "ReorderSeats")]
+    ReorderSeats { seats: Vec<String> },
+    #[serde(rename = "Undo")]
+    Undo { #[serde(default, skip_serializing_if = "Option::is_none")] count: Option<usize> },
+    #[serde(rename = "UndoVote")]
+    UndoVote { approve: bool },
+    #[serde(rename = "StartMulligans")]
+    StartMulligans { #[serde(default, skip_serializing_if = "Option::is_none")] free_first: Option<bool> },
+    #[serde(rename = "Mulligan")]
+    Mulligan {},
+    #[serde(rename = "KeepHand")]
+    KeepHand { #[serde(default)] bottom_cards: Vec<String> },
     #[serde(rename = "RestartGame")]
     RestartGame {},
     #[serde(rename = "LeaveTable")]
@@ -55,6 +117,8 @@ pub enum Message {
     RevealCard { player_id: String, card_id: String, card_name: String, zone: String },
     #[serde(rename = "Scry")]
     Scry { player_id: String, count: usize },
+    #[serde(rename = "Surveil")]
+    Surveil { player_id: String, count: usize },
     #[serde(rename = "ScryComplete")]
     ScryComplete { player_id: String, top_cards: Vec<String>, bottom_cards: Vec<String> },
     #[serde(rename = "SurveilComplete")]
@@ -65,20 +129,368 @@ pub enum Message {
     SpawnCard { player_id: String, set_code: String, collector_number: String, card_name: String, position: String, is_two_sided: bool },
     #[serde(rename = "FlipCardFace")]
     FlipCardFace { player_id: String, card_id: String },
+    #[serde(rename = "SearchLibrary")]
+    SearchLibrary { player_id: String },
+    #[serde(rename = "TutorCard")]
+    TutorCard { player_id: String, card_id: String, to_zone: String, #[serde(default)] reveal: bool, #[serde(default, skip_serializing_if = "Option::is_none")] shuffle: Option<bool> },
+    #[serde(rename = "PutInLibrary")]
+    PutInLibrary { player_id: String, card_id: String, from_zone: String, position: usize, #[serde(default)] from_bottom: bool },
+    #[serde(rename = "ExileTopFaceDown")]
+    ExileTopFaceDown { player_id: String, count: usize },
+    #[serde(rename = "RevealUntil")]
+    RevealUntil { player_id: String, card_name: String, hit_zone: String, rest_zone: String },
+    #[serde(rename = "RequestResync")]
+    RequestResync {},
+    #[serde(rename = "ListRelatedTokens")]
+    ListRelatedTokens { player_id: String, card_id: String },
+    #[serde(rename = "CreateRelatedToken")]
+    CreateRelatedToken { player_id: String, card_id: String, #[serde(default)] token_name: Option<String>, #[serde(default)] count: Option<usize> },
     
     #[serde(rename = "GameState")]
     GameState { state: String },
+    #[serde(rename = "ScryCards")]
+    ScryCards { cards: Vec<Card> },
+    #[serde(rename = "SurveilCards")]
+    SurveilCards { cards: Vec<Card> },
+    #[serde(rename = "LibraryCards")]
+    LibraryCards { cards: Vec<Card> },
+    #[serde(rename = "EventLog")]
+    EventLog { events: Vec<GameEvent> },
+    #[serde(rename = "DecklistErrors")]
+    DecklistErrors { errors: Vec<DecklistError> },
+    #[serde(rename = "RelatedTokens")]
+    RelatedTokens { card_id: String, tokens: Vec<RelatedToken> },
     #[serde(rename = "Error")]
     Error { message: String },
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WsAuthQuery {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpectateQuery {
+    pub token: String,
+    /// Seconds to hold back everything the spectator sees.
+    #[serde(default)]
+    pub delay: Option<u64>,
+}
+
+impl Message {
+    /// Whether applying this message should take an undo snapshot first.
+    fn is_undoable(&self) -> bool {
+        !matches!(
+            self,
+            Message::Undo { .. }
+                | Message::UndoVote { .. }
+                | Message::Scry { .. }
+                | Message::Surveil { .. }
+                | Message::DiceRoll { .. }
+                | Message::SearchLibrary { .. }
+                | Message::RequestResync {}
+                | Message::ListRelatedTokens { .. }
+                // These change who is at the table or start over, which clears history
+                | Message::RestartGame {}
+                | Message::LeaveTable {}
+                // Undoing a mulligan would let a player redraw until they like their hand
+                | Message::StartMulligans { .. }
+                | Message::Mulligan {}
+                | Message::KeepHand { .. }
+        )
+    }
+
+    /// Actions that have to wait until everyone has kept their opening hand.
+    fn waits_for_mulligans(&self) -> bool {
+        matches!(
+            self,
+            Message::LoadLibrary { .. }
+                | Message::LoadDeck { .. }
+                | Message::MoveCard { .. }
+                | Message::DrawCard { .. }
+                | Message::MillCard { .. }
+                | Message::DiscardCard { .. }
+                | Message::CastSpell { .. }
+                | Message::ResolveStack {}
+                | Message::NextTurn {}
+                | Message::UndoTurn {}
+                | Message::AdvanceStep {}
+                | Message::Scry { .. }
+                | Message::Surveil { .. }
+                | Message::ManifestCard { .. }
+                | Message::SearchLibrary { .. }
+                | Message::TutorCard { .. }
+                | Message::PutInLibrary { .. }
+                | Message::ExileTopFaceDown { .. }
+                | Message::RevealUntil { .. }
+        )
+    }
+}
+
+/// Shows the table cards that were revealed from a library.
+fn announce_library_reveal(game: &GameSession, player_id: &str, cards: &[Card], found: Option<&Card>) {
+    let player_name = game.players.get(player_id).map(|p| p.name.clone()).unwrap_or_else(|| "Unknown".to_string());
+    let msg = serde_json::json!({
+        "LibraryReveal": {
+            "player_id": player_id,
+            "player_name": player_name,
+            "cards": cards,
+            "found": found
+        }
+    });
+    game.broadcast_message(msg.to_string());
+}
+
+/// Tells the table how an undo request or vote turned out.
+fn announce_undo(game: &GameSession, actor_id: &str, outcome: &UndoOutcome) {
+    let actor_name = game.players.get(actor_id).map(|p| p.name.clone()).unwrap_or_else(|| "Unknown".to_string());
+    let msg = match outcome {
+        UndoOutcome::Applied { summaries } => serde_json::json!({
+            "UndoApplied": {
+                "player_name": actor_name,
+                "summaries": summaries
+            }
+        }),
+        UndoOutcome::AwaitingVotes { awaiting } => serde_json::json!({
+            "UndoRequested": {
+                "requester_id": game.pending_undo.as_ref().map(|p| p.requester_id.clone()),
+                "summaries": game.pending_undo.as_ref().map(|p| p.summaries.clone()).unwrap_or_default(),
+                "awaiting": awaiting
+            }
+        }),
+        UndoOutcome::Rejected => serde_json::json!({
+            "UndoRejected": {
+                "player_name": actor_name
+            }
+        }),
+    };
+    game.broadcast_message(msg.to_string());
+}
+
 pub async fn ws_handler(
-    Path((game_id, player_id, player_name)): Path<(String, String, String)>,
+    Path(game_id): Path<String>,
+    Query(auth): Query<WsAuthQuery>,
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-) -> impl IntoResponse {
+) -> Response {
+    // The seat is always the logged-in user's; never trust ids from the client
+    let user = match users::user_for_token(&state.db_pool, &auth.token).await {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::warn!("Rejected WebSocket join for game {}: {}", game_id, e);
+            return (StatusCode::UNAUTHORIZED, e).into_response();
+        }
+    };
+
+    // Seats are only handed out to players who passed the join check
+    match state.game_manager.read().await.get_game(&game_id) {
+        Some(game) => {
+            if let Err(e) = game.can_seat(&user.id) {
+                tracing::warn!("Rejected WebSocket join for game {}: {}", game_id, e);
+                return (StatusCode::FORBIDDEN, e).into_response();
+            }
+        }
+        None => return (StatusCode::NOT_FOUND, "Game not found").into_response(),
+    }
+
+    let player_id = user.id;
+    let player_name = user.username;
+    let game_manager = state.game_manager.clone();
+    let db_pool = state.db_pool.clone();
+    ws.on_upgrade(|socket| handle_socket(socket, game_id, player_id, player_name, game_manager, db_pool))
+        .into_response()
+}
+
+/// Longest delay a spectator may ask for, in seconds.
+const MAX_SPECTATOR_DELAY_SECS: u64 = 600;
+
+pub async fn spectate_handler(
+    Path(game_id): Path<String>,
+    Query(query): Query<SpectateQuery>,
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Response {
+    let user = match users::user_for_token(&state.db_pool, &query.token).await {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::warn!("Rejected spectator for game {}: {}", game_id, e);
+            return (StatusCode::UNAUTHORIZED, e).into_response();
+        }
+    };
+
+    // Private and password-protected games are only open to their members
+    match state.game_manager.read().await.get_game(&game_id) {
+        Some(game) => {
+            if let Err(e) = game.can_spectate(&user.id) {
+                tracing::warn!("Rejected spectator for game {}: {}", game_id, e);
+                return (StatusCode::FORBIDDEN, e).into_response();
+            }
+        }
+        None => return (StatusCode::NOT_FOUND, "Game not found").into_response(),
+    }
+
+    let delay = Duration::from_secs(query.delay.unwrap_or(0).min(MAX_SPECTATOR_DELAY_SECS));
     let game_manager = state.game_manager.clone();
-    ws.on_upgrade(|socket| handle_socket(socket, game_id, player_id, player_name, game_manager))
+    ws.on_upgrade(move |socket| handle_spectator_socket(socket, game_id, user.username, delay, game_manager))
+        .into_response()
+}
+
+/// How many past log entries a joining client receives.
+const INITIAL_EVENT_COUNT: usize = 100;
+
+type SocketSender = Arc<Mutex<futures::stream::SplitSink<WebSocket, axum::extract::ws::Message>>>;
+
+/// Sends a message to this connection only, bypassing the game broadcast.
+async fn send_to_self(sender: &SocketSender, msg: &Message) {
+    if let Ok(text) = serde_json::to_string(msg) {
+        let mut s = sender.lock().await;
+        let _ = s.send(axum::extract::ws::Message::Text(text)).await;
+    }
+}
+
+/// Waits until no other action is being applied to the game. Returns `None`
+/// if the game no longer exists.
+async fn lock_actions(game_manager: &RwLock<GameManager>, game_id: &str) -> Option<OwnedMutexGuard<()>> {
+    let action_lock = Arc::clone(&game_manager.read().await.get_game(game_id)?.action_lock);
+    Some(action_lock.lock_owned().await)
+}
+
+/// Where a connection's outgoing messages go: straight to the socket, or
+/// through a queue that holds each one back for a spectator's delay.
+#[derive(Clone)]
+enum Outlet {
+    Direct(SocketSender),
+    Delayed(mpsc::UnboundedSender<(Instant, String)>, Duration),
+}
+
+impl Outlet {
+    /// Starts the task that releases delayed messages once their time comes.
+    fn delayed(sender: SocketSender, delay: Duration) -> (Outlet, tokio::task::JoinHandle<()>) {
+        let (queue, mut pending) = mpsc::unbounded_channel::<(Instant, String)>();
+        let handle = tokio::spawn(async move {
+            while let Some((due, msg)) = pending.recv().await {
+                tokio::time::sleep_until(due).await;
+                let mut s = sender.lock().await;
+                if s.send(axum::extract::ws::Message::Text(msg)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        (Outlet::Delayed(queue, delay), handle)
+    }
+
+    /// Returns false once the connection is gone.
+    async fn send(&self, msg: String) -> bool {
+        match self {
+            Outlet::Direct(sender) => {
+                let mut s = sender.lock().await;
+                s.send(axum::extract::ws::Message::Text(msg)).await.is_ok()
+            }
+            Outlet::Delayed(queue, delay) => queue.send((Instant::now() + *delay, msg)).is_ok(),
+        }
+    }
+}
+
+/// Spawns the task that forwards game broadcasts to one connection as
+/// `viewer_id` is allowed to see them.
+fn spawn_forwarder(
+    mut rx: tokio::sync::broadcast::Receiver<Broadcast>,
+    outlet: Outlet,
+    sync: Arc<Mutex<ViewerSync>>,
+    viewer_id: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let msg = match rx.recv().await {
+                Ok(Broadcast::State(snapshot)) => match sync.lock().await.update(&snapshot, &viewer_id) {
+                    Some(msg) => msg,
+                    None => continue,
+                },
+                Ok(Broadcast::Message(msg)) => msg,
+                Ok(Broadcast::Event(event)) => {
+                    let events = vec![event.view_for(&viewer_id)];
+                    serde_json::to_string(&Message::EventLog { events }).unwrap_or_default()
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            };
+            if !outlet.send(msg).await {
+                break;
+            }
+        }
+    })
+}
+
+/// Runs a read-only connection that watches the game without taking a seat.
+/// Spectators see what a player with no cards would see, and can only ask
+/// for a resync.
+async fn handle_spectator_socket(
+    socket: WebSocket,
+    game_id: String,
+    name: String,
+    delay: Duration,
+    game_manager: Arc<RwLock<GameManager>>,
+) {
+    let (sender, mut receiver) = socket.split();
+    let sender: SocketSender = Arc::new(Mutex::new(sender));
+    let (outlet, writer_handle) = Outlet::delayed(Arc::clone(&sender), delay);
+    let viewer_sync = Arc::new(Mutex::new(ViewerSync::default()));
+
+    // Subscribe and take the first state under one lock so nothing slips between them
+    let rx = {
+        let mut gm = game_manager.write().await;
+        let Some(game) = gm.get_game_mut(&game_id) else {
+            writer_handle.abort();
+            return;
+        };
+        let Some(rx) = game.tx.as_ref().map(|tx| tx.subscribe()) else {
+            writer_handle.abort();
+            return;
+        };
+        let mut events = game.events_for(SPECTATOR_VIEWER, 0);
+        events.drain(..events.len().saturating_sub(INITIAL_EVENT_COUNT));
+        outlet.send(viewer_sync.lock().await.full(game, SPECTATOR_VIEWER)).await;
+        outlet.send(serde_json::to_string(&Message::EventLog { events }).unwrap_or_default()).await;
+        game.set_spectating(true);
+        rx
+    };
+    tracing::info!("{} is spectating game {} with a {}s delay", name, game_id, delay.as_secs());
+
+    let rx_handle = spawn_forwarder(rx, outlet.clone(), Arc::clone(&viewer_sync), SPECTATOR_VIEWER.to_string());
+
+    while let Some(Ok(msg)) = receiver.next().await {
+        if let axum::extract::ws::Message::Text(text) = msg {
+            match serde_json::from_str::<Message>(&text) {
+                Ok(Message::RequestResync {}) => {
+                    // Goes through the delay like everything else, so a
+                    // resync can't be used to peek at the live table
+                    let msg = {
+                        let gm = game_manager.read().await;
+                        match gm.get_game(&game_id) {
+                            Some(game) => viewer_sync.lock().await.full(game, SPECTATOR_VIEWER),
+                            None => continue,
+                        }
+                    };
+                    outlet.send(msg).await;
+                }
+                Ok(_) => {
+                    let message = "Spectators can't change the game".to_string();
+                    send_to_self(&sender, &Message::Error { message }).await;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    {
+        let mut gm = game_manager.write().await;
+        if let Some(game) = gm.get_game_mut(&game_id) {
+            game.set_spectating(false);
+        }
+    }
+
+    rx_handle.abort();
+    writer_handle.abort();
 }
 
 async fn handle_socket(
@@ -87,18 +499,29 @@ async fn handle_socket(
     player_id: String,
     player_name: String,
     game_manager: Arc<RwLock<GameManager>>,
+    db_pool: Arc<PgPool>,
 ) {
     let (sender, mut receiver) = socket.split();
-    let sender = Arc::new(Mutex::new(sender));
+    let sender: SocketSender = Arc::new(Mutex::new(sender));
 
-    // Get broadcast channel
+    // Get broadcast channel. Seating a player waits for any action in progress
+    let Some(action) = lock_actions(&game_manager, &game_id).await else {
+        return;
+    };
     let tx = {
         let mut gm = game_manager.write().await;
         if let Some(game) = gm.get_game_mut(&game_id) {
             let is_new_player = !game.players.contains_key(&player_id);
             
             if is_new_player {
-                let join_order = game.players.len();
+                // Someone else may have taken the last seat since the upgrade
+                if let Err(e) = game.can_seat(&player_id) {
+                    drop(gm);
+                    send_to_self(&sender, &Message::Error { message: e }).await;
+                    return;
+                }
+                game.admitted.remove(&player_id);
+                let join_order = game.seats.len();
                 let player = Player::new(player_id.clone(), player_name.clone(), join_order);
                 game.add_player(player);
                 // Broadcast state to all players so they see the new player joined
@@ -114,51 +537,86 @@ async fn handle_socket(
         }
     };
 
+    drop(action);
+
     if tx.is_none() {
         return;
     }
 
     let tx = tx.unwrap();
-    let mut rx = tx.subscribe();
+    let rx = tx.subscribe();
 
-    // Send initial game state with player's seat position
+    // Send initial game state with player's seat position, plus recent history.
+    // After that the connection is sent patches against what it already has
+    let viewer_sync = Arc::new(Mutex::new(ViewerSync::default()));
     {
         let gm = game_manager.read().await;
         if let Some(game) = gm.get_game(&game_id) {
-            let player_join_order = game.players
-                .get(&player_id)
-                .map(|p| p.join_order)
-                .unwrap_or(0);
-            
-            let state_json = serde_json::to_string(&game).unwrap_or_default();
-            let msg = serde_json::json!({
-                "GameState": {
-                    "state": state_json,
-                    "player_id": player_id,
-                    "player_join_order": player_join_order
-                }
-            }).to_string();
+            let msg = viewer_sync.lock().await.full(game, &player_id);
+            let mut events = game.events_for(&player_id, 0);
+            events.drain(..events.len().saturating_sub(INITIAL_EVENT_COUNT));
+            drop(gm);
+
             let mut s = sender.lock().await;
             let _ = s.send(axum::extract::ws::Message::Text(msg)).await;
+            drop(s);
+            send_to_self(&sender, &Message::EventLog { events }).await;
         }
     }
 
-    // Spawn task to broadcast state updates to this client
-    let sender_clone = Arc::clone(&sender);
-    let rx_handle = tokio::spawn(async move {
-        let sender = sender_clone;
-        while let Ok(msg) = rx.recv().await {
-            let mut s = sender.lock().await;
-            if s.send(axum::extract::ws::Message::Text(msg)).await.is_err() {
-                break;
-            }
-        }
-    });
+    // Forward broadcasts to this client, filtering state for this viewer
+    let rx_handle = spawn_forwarder(
+        rx,
+        Outlet::Direct(Arc::clone(&sender)),
+        Arc::clone(&viewer_sync),
+        player_id.clone(),
+    );
 
     // Handle incoming messages from client
     while let Some(Ok(msg)) = receiver.next().await {
         if let axum::extract::ws::Message::Text(text) = msg {
             if let Ok(client_msg) = serde_json::from_str::<Message>(&text) {
+                // Held until the action is logged, so the snapshot and draft
+                // below describe exactly the board the action is applied to
+                let Some(_action) = lock_actions(&game_manager, &game_id).await else {
+                    continue;
+                };
+
+                let authorized = match game_manager.read().await.get_game(&game_id) {
+                    Some(game) => permissions::authorize(&player_id, &client_msg, game),
+                    None => continue,
+                };
+                if let Err(reason) = authorized {
+                    tracing::warn!("Rejected message from {} in game {}: {}", player_id, game_id, reason);
+                    send_to_self(&sender, &Message::Error { message: reason }).await;
+                    continue;
+                }
+
+                if client_msg.waits_for_mulligans() {
+                    let deciding = game_manager.read().await
+                        .get_game(&game_id)
+                        .is_some_and(|game| game.mulligan.is_some());
+                    if deciding {
+                        let message = "Waiting for everyone to keep their opening hand".to_string();
+                        send_to_self(&sender, &Message::Error { message }).await;
+                        continue;
+                    }
+                }
+
+                // Describe the action against the state before it is applied;
+                // it is only logged if the game actually changed afterwards
+                let (draft, version_before, snapshot) = {
+                    let gm = game_manager.read().await;
+                    match gm.get_game(&game_id) {
+                        Some(game) => (
+                            events::describe(game, &player_id, &client_msg),
+                            game.version,
+                            client_msg.is_undoable().then(|| game.board_clone()),
+                        ),
+                        None => (None, 0, None),
+                    }
+                };
+
                 match client_msg {
                     Message::UpdateLife { player_id: pid, delta } => {
                         let mut gm = game_manager.write().await;
@@ -180,36 +638,14 @@ async fn handle_socket(
                     Message::UpdateCounter { player_id: pid, counter_type, delta } => {
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
-                            if let Some(player) = game.get_player_mut(&pid) {
-                                match counter_type.as_str() {
-                                    "poison" => player.poison = (player.poison + delta).max(0),
-                                    "energy" => player.energy = (player.energy + delta).max(0),
-                                    "experience" => player.experience = (player.experience + delta).max(0),
-                                    _ => {}
-                                }
+                            if game.update_counter(&pid, &counter_type, delta).is_ok() {
                                 game.broadcast_state();
                             }
                         }
                     },
                     Message::MoveCard { card_id, from_zone, to_zone, position_x, position_y } => {
-                        let from_enum = match from_zone.as_str() {
-                            "hand" => Zone::Hand,
-                            "battlefield" => Zone::Battlefield,
-                            "graveyard" => Zone::Graveyard,
-                            "exile" => Zone::Exile,
-                            "command_zone" => Zone::CommandZone,
-                            "library" => Zone::Library,
-                            _ => Zone::Hand,
-                        };
-                        let to_enum = match to_zone.as_str() {
-                            "hand" => Zone::Hand,
-                            "battlefield" => Zone::Battlefield,
-                            "graveyard" => Zone::Graveyard,
-                            "exile" => Zone::Exile,
-                            "command_zone" => Zone::CommandZone,
-                            "library" => Zone::Library,
-                            _ => Zone::Hand,
-                        };
+                        let from_enum = Zone::from_name(&from_zone).unwrap_or(Zone::Hand);
+                        let to_enum = Zone::from_name(&to_zone).unwrap_or(Zone::Hand);
                         let is_moving_to_battlefield = to_enum == Zone::Battlefield;
 
                         let mut gm = game_manager.write().await;
@@ -232,6 +668,7 @@ async fn handle_socket(
                                 if let Some(player) = game.get_player_mut(&player_id) {
                                     player.battlefield.retain(|c| c.id != card_id);
                                 }
+                                game.release_attachments(&card_id);
                             } else if game.move_card(&player_id, &card_id, from_enum, to_enum).is_ok() {
                                 // If moving to battlefield with position, update card position
                                 if is_moving_to_battlefield {
@@ -255,12 +692,7 @@ async fn handle_socket(
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             if let Some(player) = game.get_player_mut(&player_id) {
                                 // Draw one or more cards from library to hand
-                                let draw_count = count.unwrap_or(1);
-                                for _ in 0..draw_count {
-                                    if let Some(card) = player.library.pop() {
-                                        player.hand.push(card);
-                                    }
-                                }
+                                player.draw_cards(count.unwrap_or(1));
                                 game.broadcast_state();
                             }
                         }
@@ -270,7 +702,8 @@ async fn handle_socket(
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             if let Some(player) = game.get_player_mut(&player_id) {
                                 // Move one card from library to graveyard
-                                if let Some(card) = player.library.pop() {
+                                if let Some(mut card) = player.library.pop() {
+                                    card.is_revealed = false;
                                     player.graveyard.push(card);
                                     game.broadcast_state();
                                 }
@@ -282,6 +715,22 @@ async fn handle_socket(
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             game.next_turn();
                             game.broadcast_state();
+                            game.broadcast_message(game.step_message());
+                        }
+                    },
+                    Message::AdvanceStep {} => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            game.advance_step();
+                            game.broadcast_state();
+                            game.broadcast_message(game.step_message());
+                        }
+                    },
+                    Message::SetTurnAutomation { auto_untap, auto_draw } => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            game.automation = TurnAutomation { auto_untap, auto_draw };
+                            game.broadcast_state();
                         }
                     },
                     Message::UndoTurn {} => {
@@ -289,6 +738,55 @@ async fn handle_socket(
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             game.undo_turn();
                             game.broadcast_state();
+                            game.broadcast_message(game.step_message());
+                        }
+                    },
+                    Message::RandomizeStartingPlayer {} => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            if game.randomize_starting_player().is_some() {
+                                game.broadcast_state();
+                            }
+                        }
+                    },
+                    Message::ReorderSeats { seats } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.reorder_seats(seats).map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::Undo { count } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.request_undo(&player_id, count.unwrap_or(1)).map(|outcome| {
+                                    announce_undo(game, &player_id, &outcome);
+                                }),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::UndoVote { approve } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.vote_undo(&player_id, approve).map(|outcome| {
+                                    announce_undo(game, &player_id, &outcome);
+                                }),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
                         }
                     },
                     Message::RestartGame {} => {
@@ -299,14 +797,12 @@ async fn handle_socket(
                             game.broadcast_state();
                             
                             // Broadcast restart notification
-                            if let Some(tx) = &game.tx {
-                                let msg = serde_json::json!({
-                                    "GameRestarted": {
-                                        "player_name": player_name
-                                    }
-                                });
-                                let _ = tx.send(msg.to_string());
-                            }
+                            let msg = serde_json::json!({
+                                "GameRestarted": {
+                                    "player_name": player_name
+                                }
+                            });
+                            game.broadcast_message(msg.to_string());
                         }
                     },
                     Message::TapCard { player_id: pid, card_id } => {
@@ -372,6 +868,11 @@ async fn handle_socket(
                                         collector_number: card_to_copy.collector_number.clone(),
                                         position_x: card_to_copy.position_x,
                                         position_y: card_to_copy.position_y,
+                                        is_revealed: false,
+                                        counters: Default::default(),
+                                        attached_to: None,
+                                        is_aura: false,
+                                        owner_id: None,
                                     };
                                     player.battlefield.push(token);
                                     game.broadcast_state();
@@ -379,6 +880,254 @@ async fn handle_socket(
                             }
                         }
                     },
+                    Message::StartMulligans { free_first } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => {
+                                    // The free first mulligan is the usual multiplayer rule
+                                    let free_first = free_first.unwrap_or(game.seats.len() > 2);
+                                    game.start_mulligans(free_first).map(|_| {
+                                        game.broadcast_state();
+                                        game.broadcast_message(game.mulligan_message());
+                                    })
+                                }
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::Mulligan {} => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.take_mulligan(&player_id).map(|_| {
+                                    game.broadcast_state();
+                                    game.broadcast_message(game.mulligan_message());
+                                }),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::KeepHand { bottom_cards } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.keep_hand(&player_id, &bottom_cards).map(|_| {
+                                    game.broadcast_state();
+                                    game.broadcast_message(game.mulligan_message());
+                                }),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::Concede {} => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game
+                                    .eliminate(&player_id, EliminationReason::Conceded)
+                                    .map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::Reinstate { player_id: pid } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.reinstate(&pid).map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::UpdateCommanderDamage { player_id: pid, commander_id, delta } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game
+                                    .update_commander_damage(&pid, &commander_id, delta)
+                                    .map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::CastSpell { card_id, from_zone, is_permanent } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match (gm.get_game_mut(&game_id), Zone::from_name(&from_zone)) {
+                                (Some(game), Some(zone)) => game
+                                    .cast_spell(&player_id, &card_id, zone, is_permanent)
+                                    .map(|_| game.broadcast_state()),
+                                (Some(_), None) => Err(format!("Unknown zone: {}", from_zone)),
+                                (None, _) => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::ResolveStack {} => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.resolve_stack().map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::CounterSpell { card_id } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.counter_spell(&card_id).map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::ReorderStack { card_ids } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.reorder_stack(&card_ids).map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::SpawnSharedCard { set_code, collector_number, card_name } => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            let card = Card {
+                                id: format!("shared_{}", Uuid::new_v4()),
+                                name: card_name,
+                                is_tapped: false,
+                                is_flipped: false,
+                                is_commander: false,
+                                is_token: true,
+                                is_two_sided: false,
+                                is_back_face: false,
+                                set_code: Some(set_code),
+                                collector_number: Some(collector_number),
+                                position_x: 0.0,
+                                position_y: 0.0,
+                                is_revealed: false,
+                                counters: Default::default(),
+                                attached_to: None,
+                                is_aura: false,
+                                owner_id: None,
+                            };
+                            game.add_shared_card(&player_id, card);
+                            game.broadcast_state();
+                        }
+                    },
+                    Message::MoveSharedCard { card_id, x, y } => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            if game.move_shared_card(&card_id, x, y).is_ok() {
+                                game.broadcast_state();
+                            }
+                        }
+                    },
+                    Message::RemoveSharedCard { card_id } => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            if game.remove_shared_card(&card_id).is_ok() {
+                                game.broadcast_state();
+                            }
+                        }
+                    },
+                    Message::GainControl { card_id, controller_id } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game
+                                    .gain_control(&player_id, &card_id, &controller_id)
+                                    .map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::ReturnControl { card_id } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.return_control(&player_id, &card_id).map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::AttachCard { player_id: pid, card_id, host_id, is_aura } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game
+                                    .attach_card(&pid, &card_id, &host_id, is_aura)
+                                    .map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::DetachCard { player_id: pid, card_id } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game.detach_card(&pid, &card_id).map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::UpdateCardCounter { player_id: pid, card_id, counter_type, delta } => {
+                        let result = {
+                            let mut gm = game_manager.write().await;
+                            match gm.get_game_mut(&game_id) {
+                                Some(game) => game
+                                    .update_card_counter(&pid, &card_id, &counter_type, delta)
+                                    .map(|_| game.broadcast_state()),
+                                None => Ok(()),
+                            }
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
                     Message::UntapAll { player_id: pid } => {
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
@@ -409,21 +1158,17 @@ async fn handle_socket(
                     Message::MoveCardOnBattlefield { player_id: pid, card_id, x, y } => {
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
-                            if let Some(player) = game.get_player_mut(&pid) {
-                                // Update position of card on battlefield
-                                if let Some(card) = player.battlefield.iter_mut().find(|c| c.id == card_id) {
-                                    card.position_x = x;
-                                    card.position_y = y;
-                                    game.broadcast_state();
-                                }
+                            // Update position of card on battlefield, along with its attachments
+                            if game.move_on_battlefield(&pid, &card_id, x, y).is_ok() {
+                                game.broadcast_state();
                             }
                         }
                     },
                     Message::LeaveTable {} => {
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
-                            // Remove the player from the game
-                            game.players.remove(&player_id);
+                            // Remove the player and their seat from the game
+                            game.remove_player(&player_id);
                             // If no players left, delete the game session
                             if game.players.is_empty() {
                                 gm.delete_game(&game_id);
@@ -432,23 +1177,54 @@ async fn handle_socket(
                             }
                         }
                     },
-                    Message::DiceRoll { player_id: _, roll_type, result } => {
-                        // Just broadcast the dice roll to all players
-                        let gm = game_manager.read().await;
-                        if let Some(game) = gm.get_game(&game_id) {
-                            if let Some(tx) = &game.tx {
-                                let msg = serde_json::json!({
-                                    "DiceRoll": {
-                                        "roll_type": roll_type,
-                                        "result": result,
-                                        "player_name": game.players.get(&player_id).map(|p| &p.name).unwrap_or(&"Unknown".to_string()).clone()
-                                    }
-                                });
-                                let _ = tx.send(msg.to_string());
+                    Message::DiceRoll { player_id: _, roll_type } => {
+                        // The server rolls so nobody can fake a result
+                        let outcome = match dice::parse(&roll_type) {
+                            Ok(spec) => spec.roll(&mut rand::thread_rng()),
+                            Err(message) => {
+                                send_to_self(&sender, &Message::Error { message }).await;
+                                continue;
+                            }
+                        };
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            let player_name = game.players.get(&player_id).map(|p| p.name.clone()).unwrap_or_else(|| "Unknown".to_string());
+                            let msg = serde_json::json!({
+                                "DiceRoll": {
+                                    "player_id": player_id,
+                                    "player_name": player_name,
+                                    "roll_type": outcome.notation,
+                                    "rolls": outcome.rolls,
+                                    "result": outcome.result
+                                }
+                            });
+                            game.broadcast_message(msg.to_string());
+                            game.record_event(&player_id, events::describe_roll(&player_name, &outcome));
+                        }
+                    },
+                    Message::LoadLibrary { player_id: pid, card_count: _, card_type: _, decklist: Some(decklist_text) } => {
+                        // Resolve the decklist before taking the game lock
+                        let deck = decklist::load_decklist(&db_pool, &decklist_text).await;
+
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            if let Some(player) = game.get_player_mut(&pid) {
+                                player.load_deck(deck.library, deck.command_zone);
+
+                                tracing::info!(
+                                    "Loaded decklist for {}: {} library cards, {} commanders, {} unresolved lines",
+                                    pid, player.library.len(), player.command_zone.len(), deck.errors.len()
+                                );
+                                game.broadcast_state();
                             }
                         }
+                        drop(gm);
+
+                        if !deck.errors.is_empty() {
+                            send_to_self(&sender, &Message::DecklistErrors { errors: deck.errors }).await;
+                        }
                     },
-                    Message::LoadLibrary { player_id: pid, card_count, card_type: _ } => {
+                    Message::LoadLibrary { player_id: pid, card_count, card_type: _, decklist: None } => {
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             if let Some(player) = game.get_player_mut(&pid) {
@@ -476,6 +1252,11 @@ async fn handle_socket(
                                         collector_number: None,
                                         position_x: 0.0,
                                         position_y: 0.0,
+                                        is_revealed: false,
+                                        counters: Default::default(),
+                                        attached_to: None,
+                                        is_aura: false,
+                                        owner_id: None,
                                     };
                                     player.library.push(card);
                                 }
@@ -495,6 +1276,11 @@ async fn handle_socket(
                                         collector_number: None,
                                         position_x: 0.0,
                                         position_y: 0.0,
+                                        is_revealed: false,
+                                        counters: Default::default(),
+                                        attached_to: None,
+                                        is_aura: false,
+                                        owner_id: None,
                                     };
                                     player.command_zone.push(card);
                                 }
@@ -503,123 +1289,109 @@ async fn handle_socket(
                             }
                         }
                     },
+                    Message::LoadDeck { player_id: pid, deck_id } => {
+                        // Players can only load decks from their own account
+                        let saved = match decks::get_deck(&db_pool, &player_id, &deck_id).await {
+                            Ok(saved) => saved,
+                            Err(e) => {
+                                send_to_self(&sender, &Message::Error { message: format!("Failed to load deck: {}", e) }).await;
+                                continue;
+                            }
+                        };
+                        let entries: Vec<_> = saved.cards
+                            .iter()
+                            .enumerate()
+                            .map(|(i, card)| card.to_entry(i + 1))
+                            .collect();
+                        let deck = decklist::resolve_decklist(&db_pool, &entries).await;
+
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            if let Some(player) = game.get_player_mut(&pid) {
+                                player.load_deck(deck.library, deck.command_zone);
+                                tracing::info!("Loaded saved deck '{}' for {}", saved.name, pid);
+                                game.broadcast_state();
+                            }
+                        }
+                        drop(gm);
+
+                        if !deck.errors.is_empty() {
+                            send_to_self(&sender, &Message::DecklistErrors { errors: deck.errors }).await;
+                        }
+                    },
                     Message::ShuffleLibrary { player_id: pid } => {
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             if let Some(player) = game.get_player_mut(&pid) {
-                                use rand::seq::SliceRandom;
-                                let mut rng = rand::thread_rng();
-                                player.library.shuffle(&mut rng);
+                                player.shuffle_library();
                                 game.broadcast_state();
                             }
                         }
                     },
-                    Message::RevealCard { player_id: _, card_id, card_name, zone: _ } => {
-                        // Broadcast revealed card to all players
-                        let gm = game_manager.read().await;
-                        if let Some(game) = gm.get_game(&game_id) {
-                            if let Some(tx) = &game.tx {
-                                let player_name = game.players.get(&player_id).map(|p| &p.name).unwrap_or(&"Unknown".to_string()).clone();
-                                let msg = serde_json::json!({
-                                    "RevealCard": {
-                                        "card_id": card_id,
-                                        "card_name": card_name,
-                                        "player_name": player_name
-                                    }
-                                });
-                                let _ = tx.send(msg.to_string());
+                    Message::RevealCard { player_id: _, card_id, card_name, zone } => {
+                        // Mark the card as revealed so it stays visible in everyone's view,
+                        // then broadcast the reveal to all players
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            let player_name = game.players.get(&player_id).map(|p| &p.name).unwrap_or(&"Unknown".to_string()).clone();
+                            if let (Some(player), Some(zone_enum)) = (game.get_player_mut(&player_id), Zone::from_name(&zone)) {
+                                if let Some(card) = player.get_zone_mut(&zone_enum).iter_mut().find(|c| c.id == card_id) {
+                                    card.is_revealed = true;
+                                }
                             }
+                            game.broadcast_state();
+
+                            let msg = serde_json::json!({
+                                "RevealCard": {
+                                    "card_id": card_id,
+                                    "card_name": card_name,
+                                    "player_name": player_name,
+                                    "zone": zone
+                                }
+                            });
+                            game.broadcast_message(msg.to_string());
                         }
                     },
-                    Message::Scry { player_id: _, count: _ } => {
-                        // Scry is a private action - no broadcast needed
-                        // The frontend already has the library and will show the scry interface
-                        // We only process ScryComplete to update the library
+                    Message::Scry { player_id: _, count } => {
+                        // Scry is a private action - only the connection's own player sees the cards.
+                        // The top of the library is its end, so the top card comes first.
+                        let cards = {
+                            let gm = game_manager.read().await;
+                            gm.get_game(&game_id)
+                                .and_then(|game| game.get_player(&player_id))
+                                .map(|player| player.library.iter().rev().take(count).cloned().collect::<Vec<_>>())
+                        };
+                        if let Some(cards) = cards {
+                            send_to_self(&sender, &Message::ScryCards { cards }).await;
+                        }
+                    },
+                    Message::Surveil { player_id: _, count } => {
+                        let cards = {
+                            let gm = game_manager.read().await;
+                            gm.get_game(&game_id)
+                                .and_then(|game| game.get_player(&player_id))
+                                .map(|player| player.library.iter().rev().take(count).cloned().collect::<Vec<_>>())
+                        };
+                        if let Some(cards) = cards {
+                            send_to_self(&sender, &Message::SurveilCards { cards }).await;
+                        }
                     },
                     Message::ScryComplete { player_id: pid, top_cards, bottom_cards } => {
                         // Reorder library based on scry decisions
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             if let Some(player) = game.get_player_mut(&pid) {
-                                // Create mapping of card IDs for lookup
-                                let mut remaining_library: Vec<Card> = vec![];
-                                
-                                // Separate viewed cards from rest of library
-                                let mut all_top_bottom: Vec<String> = top_cards.clone();
-                                all_top_bottom.extend(bottom_cards.clone());
-                                
-                                for card in player.library.drain(..) {
-                                    if !all_top_bottom.contains(&card.id) {
-                                        remaining_library.push(card);
-                                    }
-                                }
-                                
-                                // Rebuild library: top cards, then middle, then bottom cards
-                                let mut new_library: Vec<Card> = vec![];
-                                
-                                // Add top cards in order
-                                for top_id in &top_cards {
-                                    if let Some(pos) = remaining_library.iter().position(|c| &c.id == top_id) {
-                                        new_library.push(remaining_library.remove(pos));
-                                    }
-                                }
-                                
-                                // Add remaining middle cards
-                                new_library.extend(remaining_library);
-                                
-                                // Add bottom cards in order at the end
-                                for bottom_id in &bottom_cards {
-                                    if let Some(pos) = new_library.iter().position(|c| &c.id == bottom_id) {
-                                        let card = new_library.remove(pos);
-                                        new_library.push(card);
-                                    }
-                                }
-                                
-                                player.library = new_library;
+                                player.finish_scry(&top_cards, &bottom_cards);
                                 game.broadcast_state();
                             }
                         }
                     },
                     Message::SurveilComplete { player_id: pid, top_cards, graveyard_cards } => {
-                        // Reorder library based on surveil decisions
+                        // Reorder library and fill the graveyard based on surveil decisions
                         let mut gm = game_manager.write().await;
                         if let Some(game) = gm.get_game_mut(&game_id) {
                             if let Some(player) = game.get_player_mut(&pid) {
-                                // Create mapping of card IDs for lookup
-                                let mut remaining_library: Vec<Card> = vec![];
-                                
-                                // Separate viewed cards from rest of library
-                                let mut all_viewed: Vec<String> = top_cards.clone();
-                                all_viewed.extend(graveyard_cards.clone());
-                                
-                                for card in player.library.drain(..) {
-                                    if !all_viewed.contains(&card.id) {
-                                        remaining_library.push(card);
-                                    }
-                                }
-                                
-                                // Rebuild library: top cards, then middle, then graveyard cards
-                                let mut new_library: Vec<Card> = vec![];
-                                
-                                // Add top cards in order
-                                for top_id in &top_cards {
-                                    if let Some(pos) = remaining_library.iter().position(|c| &c.id == top_id) {
-                                        new_library.push(remaining_library.remove(pos));
-                                    }
-                                }
-                                
-                                // Add remaining middle cards
-                                new_library.extend(remaining_library);
-                                
-                                // Move graveyard cards to graveyard
-                                for graveyard_id in &graveyard_cards {
-                                    if let Some(pos) = new_library.iter().position(|c| &c.id == graveyard_id) {
-                                        let card = new_library.remove(pos);
-                                        player.graveyard.push(card);
-                                    }
-                                }
-                                
-                                player.library = new_library;
+                                player.finish_surveil(&top_cards, &graveyard_cards);
                                 game.broadcast_state();
                             }
                         }
@@ -666,6 +1438,11 @@ async fn handle_socket(
                                     collector_number: Some(collector_number),
                                     position_x: 400.0,  // Center of battlefield
                                     position_y: 300.0,
+                                    is_revealed: false,
+                                    counters: Default::default(),
+                                    attached_to: None,
+                                    is_aura: false,
+                                    owner_id: None,
                                 };
                                 player.battlefield.push(card);
                                 game.broadcast_state();
@@ -699,14 +1476,182 @@ async fn handle_socket(
                             }
                         }
                     },
+                    Message::RequestResync {} => {
+                        // The client missed a patch, so start it over from the full state
+                        let msg = {
+                            let gm = game_manager.read().await;
+                            match gm.get_game(&game_id) {
+                                Some(game) => viewer_sync.lock().await.full(game, &player_id),
+                                None => continue,
+                            }
+                        };
+                        let mut s = sender.lock().await;
+                        let _ = s.send(axum::extract::ws::Message::Text(msg)).await;
+                    },
+                    Message::SearchLibrary { player_id: pid } => {
+                        // Only the searching player sees their library, sorted so the
+                        // search doesn't give away the order of the cards
+                        let cards = {
+                            let gm = game_manager.read().await;
+                            gm.get_game(&game_id)
+                                .and_then(|game| game.get_player(&pid))
+                                .map(|player| {
+                                    let mut cards = player.library.clone();
+                                    cards.sort_by(|a, b| a.name.cmp(&b.name));
+                                    cards
+                                })
+                        };
+                        if let Some(cards) = cards {
+                            send_to_self(&sender, &Message::LibraryCards { cards }).await;
+                        }
+                    },
+                    Message::TutorCard { player_id: pid, card_id, to_zone, reveal, shuffle } => {
+                        let result = match Zone::from_name(&to_zone) {
+                            Some(zone) => {
+                                let mut gm = game_manager.write().await;
+                                match gm.get_game_mut(&game_id) {
+                                    Some(game) => game
+                                        .get_player_mut(&pid)
+                                        .ok_or(format!("Player {} not found", pid))
+                                        .and_then(|player| player.tutor(&card_id, &zone, shuffle.unwrap_or(true)))
+                                        .map(|card| {
+                                            // Cards put into public zones are seen by everyone anyway
+                                            if reveal || matches!(zone, Zone::Battlefield | Zone::Graveyard | Zone::Exile) {
+                                                announce_library_reveal(game, &pid, std::slice::from_ref(&card), Some(&card));
+                                            }
+                                            game.broadcast_state();
+                                        }),
+                                    None => Ok(()),
+                                }
+                            }
+                            None => Err(format!("Unknown zone: {}", to_zone)),
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::PutInLibrary { player_id: pid, card_id, from_zone, position, from_bottom } => {
+                        let result = match Zone::from_name(&from_zone) {
+                            Some(zone) => {
+                                let mut gm = game_manager.write().await;
+                                match gm.get_game_mut(&game_id) {
+                                    Some(game) => game
+                                        .put_in_library(&pid, &card_id, zone, position, from_bottom)
+                                        .map(|_| game.broadcast_state()),
+                                    None => Ok(()),
+                                }
+                            }
+                            None => Err(format!("Unknown zone: {}", from_zone)),
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::ExileTopFaceDown { player_id: pid, count } => {
+                        let mut gm = game_manager.write().await;
+                        if let Some(game) = gm.get_game_mut(&game_id) {
+                            if let Some(player) = game.get_player_mut(&pid) {
+                                if player.exile_top_face_down(count) > 0 {
+                                    game.broadcast_state();
+                                }
+                            }
+                        }
+                    },
+                    Message::RevealUntil { player_id: pid, card_name, hit_zone, rest_zone } => {
+                        let result = match (Zone::from_name(&hit_zone), Zone::from_name(&rest_zone)) {
+                            (Some(hit_zone), Some(rest_zone)) => {
+                                let mut gm = game_manager.write().await;
+                                match gm.get_game_mut(&game_id) {
+                                    Some(game) => game
+                                        .get_player_mut(&pid)
+                                        .ok_or(format!("Player {} not found", pid))
+                                        .and_then(|player| player.reveal_until(&card_name, &hit_zone, &rest_zone))
+                                        .map(|(revealed, hit)| {
+                                            announce_library_reveal(game, &pid, &revealed, revealed.last().filter(|_| hit));
+                                            game.broadcast_state();
+                                        }),
+                                    None => Ok(()),
+                                }
+                            }
+                            _ => Err("Unknown zone".to_string()),
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
+                    Message::ListRelatedTokens { player_id: pid, card_id } => {
+                        let printing = match game_manager.read().await.get_game(&game_id) {
+                            Some(game) => game.battlefield_printing(&pid, &card_id),
+                            None => continue,
+                        };
+                        let result = match printing {
+                            Ok((set_code, collector_number)) => tokens::related_tokens(&db_pool, &set_code, &collector_number).await,
+                            Err(e) => Err(e),
+                        };
+                        match result {
+                            Ok(tokens) => send_to_self(&sender, &Message::RelatedTokens { card_id, tokens }).await,
+                            Err(message) => send_to_self(&sender, &Message::Error { message }).await,
+                        }
+                    },
+                    Message::CreateRelatedToken { player_id: pid, card_id, token_name, count } => {
+                        let printing = match game_manager.read().await.get_game(&game_id) {
+                            Some(game) => game.battlefield_printing(&pid, &card_id),
+                            None => continue,
+                        };
+                        let token = match printing {
+                            Ok((set_code, collector_number)) => tokens::related_tokens(&db_pool, &set_code, &collector_number)
+                                .await
+                                .and_then(|found| tokens::choose_token(found, token_name.as_deref())),
+                            Err(e) => Err(e),
+                        };
+
+                        let result = match token {
+                            Ok(token) => {
+                                let mut gm = game_manager.write().await;
+                                match gm.get_game_mut(&game_id) {
+                                    Some(game) => game
+                                        .create_tokens(&pid, &card_id, &token, count.unwrap_or(1))
+                                        .map(|_| game.broadcast_state()),
+                                    None => Ok(()),
+                                }
+                            }
+                            Err(e) => Err(e),
+                        };
+                        if let Err(message) = result {
+                            send_to_self(&sender, &Message::Error { message }).await;
+                        }
+                    },
                     _ => {}
                 }
+
+                let mut gm = game_manager.write().await;
+                if let Some(game) = gm.get_game_mut(&game_id) {
+                    let changed = game.version != version_before;
+
+                    if let (true, Some(before)) = (changed, snapshot) {
+                        let summary = draft.as_ref().map(|d| d.summary.clone()).unwrap_or_else(|| "an action".to_string());
+                        game.push_history(&player_id, summary, before);
+                    }
+
+                    if let (true, Some(draft)) = (changed, draft) {
+                        game.record_event(&player_id, draft);
+                    }
+
+                    // Undo votes only apply to the state they were requested for
+                    if game.expire_pending_undo() {
+                        game.broadcast_message(serde_json::json!({
+                            "UndoCancelled": {
+                                "reason": "The game changed before everyone approved"
+                            }
+                        }).to_string());
+                    }
+                }
             }
         }
     }
 
     // Mark as inactive on disconnect
-    {
+    if let Some(_action) = lock_actions(&game_manager, &game_id).await {
         let mut gm = game_manager.write().await;
         if let Some(game) = gm.get_game_mut(&game_id) {
             if let Some(player) = game.get_player_mut(&player_id) {
@@ -2,12 +2,13 @@ import React, { useState, useContext } from 'react';
 import '../styles/BattlefieldZone.css';
 import { WebSocketContext } from './GameTable';
 
-const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCounter, onSpawnCard, onZoom, playerId = null, onInspectCard = null, playmatImage = null }) => {
+const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCounter, onSpawnCard, onZoom, playerId = null, onInspectCard = null, playmatImage = null, commanders = [], commanderDamage = {}, onUpdateCommanderDamage = null, viewerId = null }) => {
   const ws = useContext(WebSocketContext);
   const [dragOverZone, setDragOverZone] = useState(false);
   const [contextMenu, setContextMenu] = useState(null);
   const [draggedBattlefieldCard, setDraggedBattlefieldCard] = useState(null);
   const [cardBeingDragged, setCardBeingDragged] = useState(null);
+  const [attaching, setAttaching] = useState(null);
   const [editingValue, setEditingValue] = useState(null);
   const [inputValue, setInputValue] = useState('');
 
@@ -179,6 +180,94 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
     setContextMenu(null);
   };
 
+  const updateCardCounter = (counterType, delta) => {
+    if (contextMenu && ws && ws.readyState === WebSocket.OPEN && playerId) {
+      ws.send(JSON.stringify({
+        UpdateCardCounter: {
+          player_id: playerId,
+          card_id: contextMenu.card.id,
+          counter_type: counterType,
+          delta
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const handleGainControl = () => {
+    if (contextMenu && ws && ws.readyState === WebSocket.OPEN && viewerId) {
+      ws.send(JSON.stringify({
+        GainControl: {
+          card_id: contextMenu.card.id,
+          controller_id: viewerId
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const handleCreateToken = () => {
+    if (contextMenu && ws && ws.readyState === WebSocket.OPEN && playerId) {
+      // The server answers with the card's tokens, GameTable asks which and how many
+      ws.send(JSON.stringify({
+        ListRelatedTokens: {
+          player_id: playerId,
+          card_id: contextMenu.card.id
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const handleReturnControl = () => {
+    if (contextMenu && ws && ws.readyState === WebSocket.OPEN) {
+      ws.send(JSON.stringify({
+        ReturnControl: {
+          card_id: contextMenu.card.id
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const startAttaching = (isAura) => {
+    if (contextMenu) {
+      setAttaching({ card_id: contextMenu.card.id, is_aura: isAura });
+    }
+    setContextMenu(null);
+  };
+
+  const handleDetach = () => {
+    if (contextMenu && ws && ws.readyState === WebSocket.OPEN && playerId) {
+      ws.send(JSON.stringify({
+        DetachCard: {
+          player_id: playerId,
+          card_id: contextMenu.card.id
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const handleReinstate = () => {
+    if (ws && ws.readyState === WebSocket.OPEN) {
+      ws.send(JSON.stringify({
+        Reinstate: {
+          player_id: player.id
+        }
+      }));
+    }
+  };
+
+  const handleCustomCounter = () => {
+    const counterType = window.prompt('Counter name');
+    if (counterType && counterType.trim()) {
+      updateCardCounter(counterType.trim(), 1);
+    } else {
+      setContextMenu(null);
+    }
+  };
+
   const sendCardTo = (toZone) => {
     if (!contextMenu || !ws || ws.readyState !== WebSocket.OPEN || !playerId) return;
 
@@ -206,6 +295,22 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
     // Don't drag if right-clicking (context menu) or already dragging
     if (e.button !== 0) return;
     e.preventDefault();
+
+    // Picking a host for a pending attachment
+    if (attaching) {
+      if (card.id !== attaching.card_id && ws && ws.readyState === WebSocket.OPEN && playerId) {
+        ws.send(JSON.stringify({
+          AttachCard: {
+            player_id: playerId,
+            card_id: attaching.card_id,
+            host_id: card.id,
+            is_aura: attaching.is_aura
+          }
+        }));
+      }
+      setAttaching(null);
+      return;
+    }
     
     setCardBeingDragged({
       card_id: card.id,
@@ -523,6 +628,28 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
               +
             </button>
           </div>
+          {onUpdateCommanderDamage && commanders
+            .filter(commander => commander.owner_id !== player.id)
+            .map(commander => (
+              <div className="counter commander-damage" key={commander.id} title={`Commander damage from ${commander.name}`}>
+                <button
+                  className="counter-btn counter-minus"
+                  onClick={() => onUpdateCommanderDamage(player.id, commander.id, -1)}
+                  title={`${commander.name} damage -1`}
+                >
+                  −
+                </button>
+                <span className="counter-icon">⚔️</span>
+                <span className="counter-value">{commanderDamage[commander.id] || 0}</span>
+                <button
+                  className="counter-btn counter-plus"
+                  onClick={() => onUpdateCommanderDamage(player.id, commander.id, 1)}
+                  title={`${commander.name} damage +1`}
+                >
+                  +
+                </button>
+              </div>
+            ))}
         </div>
       </div>
 
@@ -537,7 +664,7 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
           return (
             <div
               key={card.id}
-              className={`battlefield-card ${card.is_tapped ? 'tapped' : ''} ${draggedBattlefieldCard?.card_id === card.id ? 'being-dragged' : ''} ${card.is_commander ? 'commander' : ''}`}
+              className={`battlefield-card ${card.is_tapped ? 'tapped' : ''} ${card.attached_to ? 'attached' : ''} ${draggedBattlefieldCard?.card_id === card.id ? 'being-dragged' : ''} ${card.is_commander ? 'commander' : ''}`}
               style={{
                 position: 'absolute',
                 left: `${displayCard.position_x}px`,
@@ -546,7 +673,7 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
               }}
               onMouseDown={(e) => handleCardMouseDown(e, card)}
               onContextMenu={(e) => handleContextMenu(e, card)}
-              title="Drag to move, click to tap, right-click for options"
+              title={attaching ? 'Click a card to attach to it' : 'Drag to move, click to tap, right-click for options'}
             >
               <div
                 className="card-image"
@@ -557,6 +684,15 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
                 }}
               >
                 {card.is_token && <div className="token-label">TOKEN</div>}
+                {card.counters && Object.keys(card.counters).length > 0 && (
+                  <div className="card-counters">
+                    {Object.entries(card.counters).map(([type, count]) => (
+                      <div key={type} className="card-counter" title={`${count} ${type}`}>
+                        {type === '+1/+1' || type === '-1/-1' ? `${type} ×${count}` : `${count} ${type}`}
+                      </div>
+                    ))}
+                  </div>
+                )}
               </div>
             </div>
           );
@@ -565,6 +701,18 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
 
       {/* Active Turn Indicator */}
       {isActive && <div className={`active-badge ${position}`}>ACTIVE TURN</div>}
+      {player.is_eliminated && (
+        <div className={`eliminated-badge ${position}`}>
+          OUT OF THE GAME
+          <button
+            className="reinstate-btn"
+            onClick={handleReinstate}
+            title="Undo this elimination"
+          >
+            Reinstate
+          </button>
+        </div>
+      )}
 
       {/* Context Menu for Card Actions */}
       {contextMenu && (
@@ -586,7 +734,56 @@ const BattlefieldZone = ({ player, position, isActive, onUpdateLife, onUpdateCou
           <button className="context-menu-item" onClick={handleCopyCard}>
             Copy (Token)
           </button>
+          {playerId && !contextMenu.card.is_token && (
+            <button className="context-menu-item" onClick={handleCreateToken}>
+              Create Token...
+            </button>
+          )}
+          {!playerId && viewerId && (
+            <button className="context-menu-item" onClick={handleGainControl}>
+              Gain Control
+            </button>
+          )}
+          {contextMenu.card.owner_id && (
+            <button className="context-menu-item" onClick={handleReturnControl}>
+              Return to Owner
+            </button>
+          )}
+          {contextMenu.card.attached_to ? (
+            <button className="context-menu-item" onClick={handleDetach}>
+              Unattach
+            </button>
+          ) : (
+            <>
+              <button className="context-menu-item" onClick={() => startAttaching(true)}>
+                Attach as Aura...
+              </button>
+              <button className="context-menu-item" onClick={() => startAttaching(false)}>
+                Attach as Equipment...
+              </button>
+            </>
+          )}
           <div className="context-submenu-divider"></div>
+          <div className="context-submenu">
+            <button className="context-menu-item submenu-trigger">
+              Counters...
+            </button>
+            <div className="submenu-items">
+              {['+1/+1', '-1/-1', 'loyalty', 'charge'].map((type) => (
+                <button key={type} className="submenu-item" onClick={() => updateCardCounter(type, 1)}>
+                  Add {type}
+                </button>
+              ))}
+              <button className="submenu-item" onClick={handleCustomCounter}>
+                Add Custom...
+              </button>
+              {Object.keys(contextMenu.card.counters || {}).map((type) => (
+                <button key={`remove-${type}`} className="submenu-item" onClick={() => updateCardCounter(type, -1)}>
+                  Remove {type}
+                </button>
+              ))}
+            </div>
+          </div>
           <div className="context-submenu">
             <button className="context-menu-item submenu-trigger">
               Send To...
@@ -6,7 +6,6 @@ import { WebSocketContext } from './GameTable';
 const BottomToolbar = ({ gameState, turnNumber, onNextTurn, onAction, onGameMenu, onUndoTurn, onBack, playerId }) => {
   const ws = useContext(WebSocketContext);
   const [gameMenuOpen, setGameMenuOpen] = useState(false);
-  const [diceResult, setDiceResult] = useState(null);
 
   const keybinds = [
     { key: 'Space', action: 'Pass Priority' },
@@ -28,6 +27,32 @@ const BottomToolbar = ({ gameState, turnNumber, onNextTurn, onAction, onGameMenu
     }
   };
 
+  const handleConcede = () => {
+    if (ws && ws.readyState === WebSocket.OPEN && window.confirm('Concede this game?')) {
+      ws.send(JSON.stringify({
+        Concede: {}
+      }));
+    }
+    setGameMenuOpen(false);
+  };
+
+  const handleDealOpeningHands = () => {
+    if (ws && ws.readyState === WebSocket.OPEN && window.confirm('Shuffle every hand away and deal new opening hands?')) {
+      ws.send(JSON.stringify({
+        StartMulligans: {}
+      }));
+    }
+    setGameMenuOpen(false);
+  };
+
+  const handleAdvanceStep = () => {
+    if (ws && ws.readyState === WebSocket.OPEN) {
+      ws.send(JSON.stringify({
+        AdvanceStep: {}
+      }));
+    }
+  };
+
   const handleUntapAll = () => {
     if (ws && ws.readyState === WebSocket.OPEN && playerId) {
       ws.send(JSON.stringify({
@@ -55,6 +80,12 @@ const BottomToolbar = ({ gameState, turnNumber, onNextTurn, onAction, onGameMenu
               <button className="menu-item" onClick={onGameMenu}>
                 Restart Game (Vote)
               </button>
+              <button className="menu-item" onClick={handleDealOpeningHands}>
+                Deal Opening Hands
+              </button>
+              <button className="menu-item" onClick={handleConcede}>
+                Concede
+              </button>
               <button className="menu-item" onClick={handleLeaveTable}>
                 Leave Table
               </button>
@@ -73,12 +104,7 @@ const BottomToolbar = ({ gameState, turnNumber, onNextTurn, onAction, onGameMenu
       {/* Right Section - Turn Info & Settings */}
       <div className="toolbar-section right">
         {/* Dice and Coins */}
-        <DiceAndCoins 
-          playerId={playerId}
-          onRoll={(result) => {
-            setDiceResult(result);
-          }}
-        />
+        <DiceAndCoins playerId={playerId} />
 
         {/* Untap All Button */}
         <button 
@@ -95,6 +121,15 @@ const BottomToolbar = ({ gameState, turnNumber, onNextTurn, onAction, onGameMenu
           <span className="turn-number">{turnNumber || 1}</span>
         </div>
 
+        {/* Current Step */}
+        <button
+          className="toolbar-btn action-btn"
+          onClick={handleAdvanceStep}
+          title="Advance to the next step"
+        >
+          <span>{gameState?.step || 'Untap'} →</span>
+        </button>
+
         {/* Back a Turn Button */}
         <button 
           className="toolbar-btn action-btn"
@@ -8,16 +8,35 @@ import LibraryZone from './LibraryZone';
 import GraveyardZone from './GraveyardZone';
 import ExileZone from './ExileZone';
 import BottomToolbar from './BottomToolbar';
+import StackZone from './StackZone';
 import RevealCardOverlay from './RevealCardOverlay';
 import ScryInterface from './ScryInterface';
 import ScryCountSelector from './ScryCountSelector';
 import SurveilInterface from './SurveilInterface';
 import ZoneViewerModal from './ZoneViewerModal';
 import SpawnCardModal from './SpawnCardModal';
+import MulliganScreen from './MulliganScreen';
 
 // Create WebSocket context to avoid passing it as a prop
 export const WebSocketContext = createContext(null);
 
+// Applies a JSON Merge Patch (RFC 7386): objects merge key by key, null
+// removes a key and anything else replaces the old value
+const applyMergePatch = (target, patch) => {
+  if (patch === null || typeof patch !== 'object' || Array.isArray(patch)) {
+    return patch;
+  }
+  const result = target && typeof target === 'object' && !Array.isArray(target) ? { ...target } : {};
+  Object.entries(patch).forEach(([key, value]) => {
+    if (value === null) {
+      delete result[key];
+    } else {
+      result[key] = applyMergePatch(result[key], value);
+    }
+  });
+  return result;
+};
+
 const GameTable = ({ gameId, playerId, playerName, onBack }) => {
   const [gameState, setGameState] = useState(null);
   const [playerJoinOrder, setPlayerJoinOrder] = useState(0);
@@ -46,6 +65,8 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
   const [zoneViewerZone, setZoneViewerZone] = useState(null);
   const [zoneViewerCards, setZoneViewerCards] = useState([]);
   const ws = useRef(null);
+  const stateRef = useRef(null);
+  const versionRef = useRef(null);
   const diceRollTimeoutRef = useRef(null);
   const restartTimeoutRef = useRef(null);
   const connectionAttempts = useRef(0);
@@ -68,7 +89,9 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
     }, connectionTimeout);
 
     // Connect to WebSocket
-    const wsUrl = `ws://${window.location.hostname}:3001/ws/${gameId}/${playerId}/${playerName}`;
+    // The server takes our identity from the session token, not from the URL
+    const token = localStorage.getItem('authToken');
+    const wsUrl = `ws://${window.location.hostname}:3001/ws/${gameId}?token=${encodeURIComponent(token || '')}`;
     ws.current = new WebSocket(wsUrl);
 
     ws.current.onopen = () => {
@@ -100,15 +123,32 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
         console.log('Parsed message:', message);
         
         if (message.GameState && message.GameState.state) {
-          console.log('Received GameState, parsing inner state...');
-          const state = JSON.parse(message.GameState.state);
-          console.log('Parsed game state:', state);
+          console.log('Received GameState');
+          const inner = message.GameState.state;
+          const state = typeof inner === 'string' ? JSON.parse(inner) : inner;
+          stateRef.current = state;
+          versionRef.current = message.GameState.version;
           setGameState(state);
           // Store the player's join order for seat rotation
           if (message.GameState.player_join_order !== undefined) {
             setPlayerJoinOrder(message.GameState.player_join_order);
             console.log('Player join order:', message.GameState.player_join_order);
           }
+        } else if (message.StatePatch) {
+          const { base_version, version, patch, player_join_order } = message.StatePatch;
+          if (stateRef.current === null || base_version !== versionRef.current) {
+            // We missed a change somewhere, so ask for the whole state again
+            console.warn('State patch out of sequence, requesting resync');
+            ws.current.send(JSON.stringify({ RequestResync: {} }));
+          } else {
+            const state = applyMergePatch(stateRef.current, patch);
+            stateRef.current = state;
+            versionRef.current = version;
+            setGameState(state);
+            if (player_join_order !== undefined) {
+              setPlayerJoinOrder(player_join_order);
+            }
+          }
         } else if (message.DiceRoll) {
           setDiceRoll(message.DiceRoll);
           // Auto-hide dice roll after 4 seconds
@@ -130,6 +170,82 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
         } else if (message.Error) {
           setError(message.Error.message);
           setTimeout(() => setError(''), 5000);
+        } else if (message.ScryCards) {
+          // Sent top card first; put them back in library order
+          setScryCards([...message.ScryCards.cards].reverse());
+          setScryActive(true);
+        } else if (message.SurveilCards) {
+          // Sent top card first; put them back in library order
+          setSurveilCards([...message.SurveilCards.cards].reverse());
+          setSurveilActive(true);
+        } else if (message.LibraryCards) {
+          setZoneViewerZone('Library');
+          setZoneViewerCards(message.LibraryCards.cards);
+        } else if (message.LibraryReveal && message.LibraryReveal.found) {
+          // The full list of revealed cards is in the event log
+          const { player_name, found } = message.LibraryReveal;
+          setRevealedCard({ id: found.id, name: found.name });
+          setRevealedCardPlayer(player_name || 'Player');
+          setRevealedCardZone('library');
+        } else if (message.DecklistErrors) {
+          const lines = message.DecklistErrors.errors
+            .map(e => `Line ${e.line_number}: ${e.message}`)
+            .join('; ');
+          setError(`Some decklist lines could not be loaded: ${lines}`);
+          setTimeout(() => setError(''), 10000);
+        } else if (message.RelatedTokens) {
+          const { card_id, tokens } = message.RelatedTokens;
+          if (tokens.length === 0) {
+            setError("That card doesn't make any known tokens");
+            setTimeout(() => setError(''), 5000);
+            return;
+          }
+          let token = tokens[0];
+          if (tokens.length > 1) {
+            const options = tokens.map((t, i) => `${i + 1}. ${t.name}`).join('\n');
+            const choice = parseInt(window.prompt(`Which token?\n${options}`, '1'), 10);
+            token = tokens[choice - 1];
+            if (!token) return;
+          }
+          const count = parseInt(window.prompt(`How many ${token.name} tokens?`, '1'), 10);
+          if (count > 0) {
+            ws.current.send(JSON.stringify({
+              CreateRelatedToken: {
+                player_id: playerId,
+                card_id,
+                token_name: token.name,
+                count
+              }
+            }));
+          }
+        } else if (message.UndoRequested) {
+          if (message.UndoRequested.awaiting.includes(playerId)) {
+            const actions = message.UndoRequested.summaries.join('\n');
+            const approve = window.confirm(`Another player wants to undo:\n${actions}\n\nAllow it?`);
+            ws.current.send(JSON.stringify({ UndoVote: { approve } }));
+          }
+        } else if (message.UndoRejected || message.UndoCancelled) {
+          setError(message.UndoRejected
+            ? `${message.UndoRejected.player_name} declined the undo`
+            : message.UndoCancelled.reason);
+          setTimeout(() => setError(''), 5000);
+        } else if (message.SpectatorCount) {
+          // Not a game change, so the version stays where it is
+          if (stateRef.current !== null) {
+            const state = { ...stateRef.current, spectators: message.SpectatorCount.count };
+            stateRef.current = state;
+            setGameState(state);
+          }
+        } else if (message.IdleWarning) {
+          const minutes = Math.max(1, Math.round(message.IdleWarning.expires_in_secs / 60));
+          setError(`This game has been idle and will close in about ${minutes} minute${minutes === 1 ? '' : 's'} unless someone makes a move`);
+          setTimeout(() => setError(''), 10000);
+        } else if (message.GameExpired) {
+          setSessionValid(false);
+          setError(`${message.GameExpired.reason}. Returning to lobby...`);
+          setTimeout(() => {
+            onBack();
+          }, 2000);
         } else if (message.RevealCard) {
           // Display revealed card to all players
           setRevealedCard({
@@ -227,6 +343,20 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
     }
   };
 
+  const updateCommanderDamage = (victimId, commanderId, delta) => {
+    if (ws.current && ws.current.readyState === WebSocket.OPEN) {
+      ws.current.send(
+        JSON.stringify({
+          UpdateCommanderDamage: {
+            player_id: victimId,
+            commander_id: commanderId,
+            delta
+          }
+        })
+      );
+    }
+  };
+
   const handleNextTurn = () => {
     if (ws.current && ws.current.readyState === WebSocket.OPEN) {
       ws.current.send(
@@ -333,7 +463,15 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
   const handleScryCountConfirm = (count) => {
     setScryCount(count);
     setScryCountSelector(false);
-    setScryActive(true);
+    // The library is hidden in the game state; ask the server for the top cards
+    if (ws.current && ws.current.readyState === WebSocket.OPEN) {
+      ws.current.send(JSON.stringify({
+        Scry: {
+          player_id: playerId,
+          count: count
+        }
+      }));
+    }
   };
 
   const handleScryComplete = (topCards, bottomCards) => {
@@ -366,7 +504,14 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
   const handleSurveilCountConfirm = (count) => {
     setSurveilCount(count);
     setSurveilCountSelector(false);
-    setSurveilActive(true);
+    if (ws.current && ws.current.readyState === WebSocket.OPEN) {
+      ws.current.send(JSON.stringify({
+        Surveil: {
+          player_id: playerId,
+          count: count
+        }
+      }));
+    }
   };
 
   const handleSurveilComplete = (topCards, graveyardCards) => {
@@ -443,6 +588,13 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
     return <div className="game-table-new loading">Waiting for players to join...</div>;
   }
   
+  // Every commander at the table, for tracking commander damage
+  const commanders = players.flatMap(p =>
+    [...(p.command_zone || []), ...(p.battlefield || [])]
+      .filter(c => c.is_commander)
+      .map(c => ({ id: c.id, name: c.name, owner_id: p.id }))
+  );
+
   // Sort players by join_order to ensure correct positions
   const sortedPlayers = [...players].sort((a, b) => a.join_order - b.join_order);
   
@@ -479,6 +631,28 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
     activeRotatedIndex = 3; // TL player is active
   }
 
+  // Opening hands are dealt and kept on the server; this player's part of it, if any
+  const mulligan = gameState?.mulligan;
+  const myMulligan = mulligan?.decisions?.[playerId];
+  const bottomFor = (mulligans) => (mulligan?.free_first ? Math.max(mulligans - 1, 0) : mulligans);
+  const mulliganWaitingFor = mulligan
+    ? Object.entries(mulligan.decisions)
+        .filter(([, decision]) => !decision.kept)
+        .map(([id]) => gameState.players[id]?.name || id)
+    : [];
+
+  const handleMulligan = () => {
+    if (ws.current && ws.current.readyState === WebSocket.OPEN) {
+      ws.current.send(JSON.stringify({ Mulligan: {} }));
+    }
+  };
+
+  const handleKeepHand = (bottomCards) => {
+    if (ws.current && ws.current.readyState === WebSocket.OPEN) {
+      ws.current.send(JSON.stringify({ KeepHand: { bottom_cards: bottomCards } }));
+    }
+  };
+
   // Get the zoomed player and their index if zoomed
   let zoomedPlayer = null;
   let zoomedIndex = -1;
@@ -508,10 +682,11 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
         <div className="dice-roll-broadcast">
           <div className="broadcast-content">
             <span className="broadcast-player">
-              {diceRoll.roll_type === 'coin' ? `${diceRoll.player_name} flipped:` : `${diceRoll.player_name} rolled:`}
+              {diceRoll.roll_type.includes('coin') ? `${diceRoll.player_name} flipped:` : `${diceRoll.player_name} rolled ${diceRoll.roll_type}:`}
             </span>
             <span className="broadcast-result">
-              {diceRoll.roll_type === 'coin' ? '🪙' : '🎲'} {diceRoll.result}
+              {diceRoll.roll_type.includes('coin') ? '🪙' : '🎲'} {diceRoll.result}
+              {diceRoll.rolls && diceRoll.rolls.length > 1 && ` (${diceRoll.rolls.join(', ')})`}
             </span>
           </div>
         </div>
@@ -523,6 +698,10 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
           <div className="zoomed-player-container">
             <BattlefieldZone 
               player={zoomedPlayer}
+              commanders={commanders}
+              commanderDamage={gameState?.commander_damage?.[zoomedPlayer?.id] || {}}
+              onUpdateCommanderDamage={updateCommanderDamage}
+              viewerId={playerId}
               position={zoomedPosition}
               isActive={activeRotatedIndex === zoomedIndex}
               onUpdateLife={updatePlayerLife}
@@ -628,6 +807,20 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
         />
       )}
 
+      {/* Opening hand decision */}
+      {myMulligan && (
+        <MulliganScreen
+          cards={gameState.players[playerId]?.hand || []}
+          mulliganCount={myMulligan.mulligans}
+          cardsToBottom={bottomFor(myMulligan.mulligans)}
+          canMulligan={bottomFor(myMulligan.mulligans + 1) < 7}
+          hasKept={myMulligan.kept}
+          waitingFor={mulliganWaitingFor}
+          onMulligan={handleMulligan}
+          onKeepHand={handleKeepHand}
+        />
+      )}
+
       {/* Scry Interface */}
       {scryActive && (
         <ScryInterface
@@ -673,7 +866,7 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
       <div className="game-container" style={{ display: zoomedPlayer ? 'none' : 'flex' }}>
         {/* Left Sidebar */}
         <div className="sidebar-section">
-          <LeftSidebar gameId={gameId} playerId={playerId} />
+          <LeftSidebar gameId={gameId} playerId={playerId} spectators={gameState?.spectators || 0} />
         </div>
 
         {/* Main Game Area */}
@@ -686,6 +879,10 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
               <div className="board-cell top-left">
                 <BattlefieldZone 
                   player={rotatedPlayers[3]}
+                  commanders={commanders}
+                  commanderDamage={gameState?.commander_damage?.[rotatedPlayers[3]?.id] || {}}
+                  onUpdateCommanderDamage={updateCommanderDamage}
+                  viewerId={playerId}
                   position="top-left"
                   isActive={activeRotatedIndex === 3}
                   onUpdateLife={updatePlayerLife}
@@ -699,6 +896,10 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
               <div className="board-cell top-right">
                 <BattlefieldZone 
                   player={rotatedPlayers[2]}
+                  commanders={commanders}
+                  commanderDamage={gameState?.commander_damage?.[rotatedPlayers[2]?.id] || {}}
+                  onUpdateCommanderDamage={updateCommanderDamage}
+                  viewerId={playerId}
                   position="top-right"
                   isActive={activeRotatedIndex === 2}
                   onUpdateLife={updatePlayerLife}
@@ -716,6 +917,10 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
               <div className="board-cell bottom-left">
                 <BattlefieldZone 
                   player={rotatedPlayers[0]}
+                  commanders={commanders}
+                  commanderDamage={gameState?.commander_damage?.[rotatedPlayers[0]?.id] || {}}
+                  onUpdateCommanderDamage={updateCommanderDamage}
+                  viewerId={playerId}
                   position="bottom-left"
                   isActive={activeRotatedIndex === 0}
                   onUpdateLife={updatePlayerLife}
@@ -731,6 +936,10 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
               <div className="board-cell bottom-right">
                 <BattlefieldZone 
                   player={rotatedPlayers[1]}
+                  commanders={commanders}
+                  commanderDamage={gameState?.commander_damage?.[rotatedPlayers[1]?.id] || {}}
+                  onUpdateCommanderDamage={updateCommanderDamage}
+                  viewerId={playerId}
                   position="bottom-right"
                   isActive={activeRotatedIndex === 1}
                   onUpdateLife={updatePlayerLife}
@@ -741,6 +950,9 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
                 />
               </div>
             </div>
+
+            {/* Stack and shared cards in the middle of the table */}
+            <StackZone gameState={gameState} playerId={playerId} />
           </div>
 
           {/* Current Player's Hand and Command Zone */}
@@ -839,6 +1051,8 @@ const GameTable = ({ gameId, playerId, playerName, onBack }) => {
       />
 
       <BottomToolbar
+        gameState={gameState}
+        turnNumber={gameState?.turn_number}
         onAction={handleAction}
         onGameMenu={handleGameMenu}
         onUndoTurn={handleUndoTurn}
@@ -141,6 +141,19 @@ const HandZone = ({ cards, onSelectCard, onHandOptions, scale = 1, playerId = nu
     setContextMenu(null);
   };
 
+  const handleCast = (isPermanent) => {
+    if (contextMenu && ws && ws.readyState === WebSocket.OPEN && playerId) {
+      ws.send(JSON.stringify({
+        CastSpell: {
+          card_id: contextMenu.card.id,
+          from_zone: 'hand',
+          is_permanent: isPermanent
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
   const sendCardTo = (toZone) => {
     if (!contextMenu || !ws || ws.readyState !== WebSocket.OPEN || !playerId) return;
 
@@ -360,6 +373,12 @@ const HandZone = ({ cards, onSelectCard, onHandOptions, scale = 1, playerId = nu
           <button className="context-menu-item" onClick={handleReveal}>
             Reveal
           </button>
+          <button className="context-menu-item" onClick={() => handleCast(true)}>
+            Cast (Permanent)
+          </button>
+          <button className="context-menu-item" onClick={() => handleCast(false)}>
+            Cast (Instant/Sorcery)
+          </button>
           <div className="context-submenu-divider"></div>
           <div className="context-submenu">
             <button className="context-menu-item submenu-trigger">
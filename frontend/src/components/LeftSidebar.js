@@ -2,13 +2,14 @@ import React, { useState, useContext } from 'react';
 import '../styles/LeftSidebar.css';
 import { WebSocketContext } from './GameTable';
 
-const LeftSidebar = ({ gameId, playerId }) => {
+const LeftSidebar = ({ gameId, playerId, spectators = 0 }) => {
   const ws = useContext(WebSocketContext);
   const [sidebarVisible, setSidebarVisible] = useState(false);
   const [expandedMenus, setExpandedMenus] = useState({
     library: false
   });
   const [showLoadLibraryModal, setShowLoadLibraryModal] = useState(false);
+  const [decklistText, setDecklistText] = useState('');
 
   const toggleMenu = (menu) => {
     setExpandedMenus(prev => ({
@@ -60,6 +61,20 @@ const LeftSidebar = ({ gameId, playerId }) => {
     }
   };
 
+  const handleLoadDecklist = () => {
+    if (ws && ws.readyState === WebSocket.OPEN && decklistText.trim()) {
+      ws.send(JSON.stringify({
+        LoadLibrary: {
+          player_id: playerId,
+          card_count: 0,
+          card_type: 'decklist',
+          decklist: decklistText
+        }
+      }));
+      setShowLoadLibraryModal(false);
+    }
+  };
+
   return (
     <div className={`left-sidebar ${!sidebarVisible ? 'collapsed' : ''}`}>
       <div className="sidebar-header">
@@ -81,6 +96,11 @@ const LeftSidebar = ({ gameId, playerId }) => {
             <div className="game-code-display">
               <span className="code-value">{gameId}</span>
             </div>
+            {spectators > 0 && (
+              <div className="spectator-count">
+                {spectators} {spectators === 1 ? 'spectator' : 'spectators'} watching
+              </div>
+            )}
           </div>
 
           {/* Library Section */}
@@ -129,6 +149,21 @@ const LeftSidebar = ({ gameId, playerId }) => {
                 <span className="option-icon">⚪</span>
                 <span className="option-name">100 Blank White Cards</span>
               </button>
+              <textarea
+                className="decklist-input"
+                rows={10}
+                placeholder={'Commander\n1 Atraxa, Praetors\' Voice (C16) 28\n\nDeck\n1 Sol Ring (C21) 263'}
+                value={decklistText}
+                onChange={(e) => setDecklistText(e.target.value)}
+              />
+              <button 
+                className="library-option"
+                onClick={handleLoadDecklist}
+                disabled={!decklistText.trim()}
+              >
+                <span className="option-icon">📜</span>
+                <span className="option-name">Import Decklist</span>
+              </button>
             </div>
           </div>
         </div>
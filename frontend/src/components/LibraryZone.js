@@ -30,7 +30,8 @@ const LibraryZone = ({ cards = [], playerId = null, playerName = null, onInspect
 
   const handleReveal = () => {
     if (cards && cards.length > 0 && onReveal) {
-      onReveal(cards[0], 'library');
+      // The top of the library is the end of the list
+      onReveal(cards[cards.length - 1], 'library');
     }
     setContextMenu(null);
   };
@@ -55,7 +56,7 @@ const LibraryZone = ({ cards = [], playerId = null, playerName = null, onInspect
       ws.send(JSON.stringify({
         ManifestCard: {
           player_id: playerId,
-          card_id: cards[0]?.id,
+          card_id: cards[cards.length - 1]?.id,
           position_x: 250,
           position_y: 250
         }
@@ -64,6 +65,51 @@ const LibraryZone = ({ cards = [], playerId = null, playerName = null, onInspect
     setContextMenu(null);
   };
 
+  const handleSearchLibrary = () => {
+    if (ws && ws.readyState === WebSocket.OPEN && playerId) {
+      ws.send(JSON.stringify({
+        SearchLibrary: {
+          player_id: playerId
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const handleExileFaceDown = () => {
+    const count = parseInt(window.prompt('Exile how many cards face down?', '1'), 10);
+    if (count > 0 && ws && ws.readyState === WebSocket.OPEN && playerId) {
+      ws.send(JSON.stringify({
+        ExileTopFaceDown: {
+          player_id: playerId,
+          count
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
+  const handleRevealUntil = () => {
+    const cardName = window.prompt('Reveal cards until you find which card?');
+    if (cardName && cardName.trim() && ws && ws.readyState === WebSocket.OPEN && playerId) {
+      const hitZone = window.confirm('Put the card you find onto the battlefield? (Cancel puts it into your hand)')
+        ? 'battlefield'
+        : 'hand';
+      const restZone = window.confirm('Put the other revealed cards on the bottom in a random order? (Cancel puts them into your graveyard)')
+        ? 'library'
+        : 'graveyard';
+      ws.send(JSON.stringify({
+        RevealUntil: {
+          player_id: playerId,
+          card_name: cardName.trim(),
+          hit_zone: hitZone,
+          rest_zone: restZone
+        }
+      }));
+    }
+    setContextMenu(null);
+  };
+
   const handleMillCard = () => {
     setShowMillSelector(true);
     setContextMenu(null);
@@ -161,6 +207,15 @@ const LibraryZone = ({ cards = [], playerId = null, playerName = null, onInspect
           <button className="context-menu-item" onClick={handleManifest}>
             Manifest
           </button>
+          <button className="context-menu-item" onClick={handleSearchLibrary}>
+            Search Library
+          </button>
+          <button className="context-menu-item" onClick={handleExileFaceDown}>
+            Exile Top Face Down...
+          </button>
+          <button className="context-menu-item" onClick={handleRevealUntil}>
+            Reveal Until...
+          </button>
         </div>
       )}
 
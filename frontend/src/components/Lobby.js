@@ -17,12 +17,20 @@ const Lobby = ({ onStartGame }) => {
   const [passwordLoading, setPasswordLoading] = useState(false);
   const [uploadError, setUploadError] = useState('');
   const [uploadLoading, setUploadLoading] = useState('');
+  const [openGames, setOpenGames] = useState([]);
+  const [newGameSettings, setNewGameSettings] = useState({
+    isPublic: true,
+    password: '',
+    maxPlayers: 4,
+    format: 'Commander',
+  });
+  const [joinPassword, setJoinPassword] = useState('');
   const gameIdInputRef = useRef(null);
 
   useEffect(() => {
     // Check if user is logged in from localStorage (on mount)
     const user = localStorage.getItem('currentUser');
-    if (user) {
+    if (user && localStorage.getItem('authToken')) {
       const userData = JSON.parse(user);
       setCurrentUser(userData);
     }
@@ -35,7 +43,36 @@ const Lobby = ({ onStartGame }) => {
     }
   }, [currentUser]);
 
+  const refreshOpenGames = async () => {
+    try {
+      const response = await fetch('/games');
+      const data = await response.json();
+      if (data.success) {
+        setOpenGames(data.games);
+      }
+    } catch (err) {
+      console.error('Failed to load open games:', err);
+    }
+  };
+
+  useEffect(() => {
+    if (currentUser && !gameSelected) {
+      refreshOpenGames();
+    }
+  }, [currentUser, gameSelected]);
+
   const handleLogout = () => {
+    const token = localStorage.getItem('authToken');
+    if (token) {
+      fetch('/auth/logout', {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+        },
+        body: JSON.stringify({ token }),
+      }).catch(() => {});
+    }
+    localStorage.removeItem('authToken');
     localStorage.removeItem('currentUser');
     setCurrentUser(null);
     setPlayerName('');
@@ -57,8 +94,25 @@ const Lobby = ({ onStartGame }) => {
     setLoading(true);
     setError('');
     try {
-      const response = await fetch('/game/create');
+      const response = await fetch('/game/create', {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+          Authorization: `Bearer ${localStorage.getItem('authToken')}`,
+        },
+        body: JSON.stringify({
+          is_public: newGameSettings.isPublic,
+          password: newGameSettings.password || null,
+          max_players: Number(newGameSettings.maxPlayers),
+          format: newGameSettings.format,
+        }),
+      });
       const data = await response.json();
+      if (!data.success) {
+        setError(data.message || 'Failed to create game');
+        setLoading(false);
+        return;
+      }
       setGameId(data.game_id);
       setGameSelected(true);
       setLoading(false);
@@ -68,7 +122,7 @@ const Lobby = ({ onStartGame }) => {
     }
   };
 
-  const handleJoinGame = () => {
+  const handleJoinGame = async () => {
     if (!gameId) {
       setError('Please enter a game ID');
       return;
@@ -77,15 +131,35 @@ const Lobby = ({ onStartGame }) => {
       setError('Player name not found. Please log in again.');
       return;
     }
-    // Generate and store a persistent player ID for this game session
-    // This ensures the player maintains their seat if they reload the page
-    let playerId = sessionStorage.getItem(`playerId_${gameId}`);
-    if (!playerId) {
-      playerId = `player_${Date.now()}`;
-      sessionStorage.setItem(`playerId_${gameId}`, playerId);
+    setError('');
+    // The server checks seats and the password before letting us sit down
+    try {
+      const response = await fetch(`/game/${gameId.toUpperCase()}/join`, {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+          Authorization: `Bearer ${localStorage.getItem('authToken')}`,
+        },
+        body: JSON.stringify({ password: joinPassword || null }),
+      });
+      const data = await response.json();
+      if (!data.success) {
+        setError(data.message || 'Failed to join game');
+        return;
+      }
+    } catch (err) {
+      setError('Failed to join game');
+      return;
     }
+    // The server seats players by their account, so the user id is the player id
     // Ensure gameId is uppercase before sending
-    onStartGame(gameId.toUpperCase(), playerId, playerName);
+    onStartGame(gameId.toUpperCase(), currentUser.id, playerName);
+  };
+
+  const handleBrowseJoin = (game) => {
+    setError('');
+    setGameId(game.game_id);
+    setGameSelected(true);
   };
 
   const handlePasswordChange = async (e) => {
@@ -118,6 +192,7 @@ const Lobby = ({ onStartGame }) => {
         body: JSON.stringify({
           username: currentUser.username,
           new_password: newPassword,
+          token: localStorage.getItem('authToken'),
         }),
       });
 
@@ -206,18 +281,26 @@ const Lobby = ({ onStartGame }) => {
         <h1>Game Table</h1>
         <div className="game-info">
           <p className="game-id">Game ID: <strong>{gameId}</strong></p>
+          <input
+            type="password"
+            placeholder="Game password (if any)"
+            value={joinPassword}
+            onChange={(e) => setJoinPassword(e.target.value)}
+            className="input"
+            autoComplete="off"
+          />
           <button onClick={handleJoinGame} className="btn btn-primary">
             Join Game
           </button>
           <button onClick={() => {
-            // Clear the stored player ID for this game when going back
-            sessionStorage.removeItem(`playerId_${gameId}`);
             setGameSelected(false);
             setGameId('');
-            setPlayerName('');
+            setJoinPassword('');
+            setError('');
           }} className="btn btn-secondary">
             Back
           </button>
+          {error && <p className="error">{error}</p>}
         </div>
       </div>
     );
@@ -347,6 +430,46 @@ const Lobby = ({ onStartGame }) => {
       )}
       
       <div className="lobby-buttons">
+        <div className="new-game-settings">
+          <label>
+            Format
+            <input
+              type="text"
+              value={newGameSettings.format}
+              onChange={(e) => setNewGameSettings({ ...newGameSettings, format: e.target.value })}
+              className="input"
+            />
+          </label>
+          <label>
+            Max players
+            <input
+              type="number"
+              min="2"
+              max="8"
+              value={newGameSettings.maxPlayers}
+              onChange={(e) => setNewGameSettings({ ...newGameSettings, maxPlayers: e.target.value })}
+              className="input"
+            />
+          </label>
+          <label>
+            Password (optional)
+            <input
+              type="password"
+              value={newGameSettings.password}
+              onChange={(e) => setNewGameSettings({ ...newGameSettings, password: e.target.value })}
+              className="input"
+              autoComplete="new-password"
+            />
+          </label>
+          <label className="checkbox-label">
+            <input
+              type="checkbox"
+              checked={newGameSettings.isPublic}
+              onChange={(e) => setNewGameSettings({ ...newGameSettings, isPublic: e.target.checked })}
+            />
+            List in the game browser
+          </label>
+        </div>
         <button
           onClick={handleCreateGame}
           disabled={loading}
@@ -376,6 +499,31 @@ const Lobby = ({ onStartGame }) => {
           Join Existing Game
         </button>
       </div>
+
+      <div className="game-browser">
+        <div className="game-browser-header">
+          <h3>Open Games</h3>
+          <button onClick={refreshOpenGames} className="btn btn-secondary">Refresh</button>
+        </div>
+        {openGames.length === 0 ? (
+          <p className="game-browser-empty">No open games right now</p>
+        ) : (
+          <ul className="game-browser-list">
+            {openGames.map((game) => (
+              <li key={game.game_id} className="game-browser-row">
+                <span className="game-browser-format">{game.format}</span>
+                <span>{game.host || 'Anonymous'}</span>
+                <span>{game.seats_filled}/{game.max_players}</span>
+                <span>{game.has_password ? '🔒' : ''}</span>
+                <span>{new Date(game.created_at * 1000).toLocaleTimeString()}</span>
+                <button onClick={() => handleBrowseJoin(game)} className="btn btn-primary">
+                  Join
+                </button>
+              </li>
+            ))}
+          </ul>
+        )}
+      </div>
       {error && <p className="error">{error}</p>}
     </div>
   );
@@ -30,8 +30,9 @@ const Login = ({ onLoginSuccess }) => {
       const data = await response.json();
 
       if (data.success && data.user) {
-        // Store user info in localStorage
+        // Store user info and session token in localStorage
         localStorage.setItem('currentUser', JSON.stringify(data.user));
+        localStorage.setItem('authToken', data.token);
         onLoginSuccess(data.user);
       } else {
         setError(data.message || 'Authentication failed');
@@ -1,16 +1,50 @@
-import React, { useState } from 'react';
+import React, { useState, useEffect } from 'react';
 import '../styles/MulliganScreen.css';
 
-const MulliganScreen = ({ cards, onKeepHand, onMulligan, mulliganCount = 0 }) => {
-  const [selectedCard, setSelectedCard] = useState(null);
-  const maxMulligans = 2;
+const MulliganScreen = ({ cards, onKeepHand, onMulligan, mulliganCount = 0, cardsToBottom = 0, canMulligan = true, hasKept = false, waitingFor = [] }) => {
+  const [bottomCards, setBottomCards] = useState([]);
+
+  // A new hand means a new choice of what to bottom
+  useEffect(() => {
+    setBottomCards([]);
+  }, [mulliganCount]);
+
+  const toggleBottom = (cardId) => {
+    if (bottomCards.includes(cardId)) {
+      setBottomCards(bottomCards.filter(id => id !== cardId));
+    } else if (bottomCards.length < cardsToBottom) {
+      setBottomCards([...bottomCards, cardId]);
+    }
+  };
 
   const handleMulligan = () => {
-    if (mulliganCount < maxMulligans) {
+    if (canMulligan) {
       onMulligan();
     }
   };
 
+  const getCardImagePath = (card) => {
+    if (card.set_code && card.collector_number) {
+      return `/GameTableData/Sets/${card.set_code}/${card.set_code}/${card.collector_number}.jpg`;
+    }
+    return '/GameTableData/General/blank.jpg';
+  };
+
+  if (hasKept) {
+    return (
+      <div className="mulligan-overlay">
+        <div className="mulligan-container">
+          <div className="mulligan-header">
+            <h1>Hand Kept</h1>
+            <p className="mulligan-subtitle">
+              Waiting for {waitingFor.join(', ') || 'other players'}
+            </p>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
   return (
     <div className="mulligan-overlay">
       <div className="mulligan-container">
@@ -19,7 +53,7 @@ const MulliganScreen = ({ cards, onKeepHand, onMulligan, mulliganCount = 0 }) =>
           <p className="mulligan-subtitle">
             {mulliganCount === 0 
               ? 'Decide whether to keep this hand' 
-              : `Mulligan ${mulliganCount} (${maxMulligans - mulliganCount} left)`}
+              : `Mulligan ${mulliganCount}`}
           </p>
         </div>
 
@@ -30,20 +64,19 @@ const MulliganScreen = ({ cards, onKeepHand, onMulligan, mulliganCount = 0 }) =>
               cards.map((card, index) => (
                 <div
                   key={card.id || index}
-                  className={`mulligan-card ${selectedCard === index ? 'selected' : ''}`}
+                  className={`mulligan-card ${bottomCards.includes(card.id) ? 'selected' : ''}`}
                   style={{
                     transform: `translateX(${(index - Math.floor(cards.length / 2)) * 70}px) rotateY(${(index - Math.floor(cards.length / 2)) * 12}deg)`,
-                    zIndex: selectedCard === index ? 20 : index
+                    zIndex: bottomCards.includes(card.id) ? 20 : index,
+                    backgroundImage: `url('${getCardImagePath(card)}')`,
+                    backgroundSize: 'cover'
                   }}
-                  onClick={() => setSelectedCard(index)}
+                  onClick={() => toggleBottom(card.id)}
+                  title={card.name}
                 >
                   <div className="card-content">
-                    <div className="card-art">
-                      <div className="mana-cost">2</div>
-                    </div>
                     <div className="card-details">
                       <div className="card-name">{card.name}</div>
-                      <div className="card-type">Creature</div>
                     </div>
                   </div>
                 </div>
@@ -56,49 +89,36 @@ const MulliganScreen = ({ cards, onKeepHand, onMulligan, mulliganCount = 0 }) =>
 
         {/* Action Menu */}
         <div className="mulligan-actions">
-          <div className="action-buttons">
-            {selectedCard !== null && (
-              <>
-                <button className="action-btn secondary">
-                  Bottom of Library
-                </button>
-                <button className="action-btn secondary">
-                  Exile
-                </button>
-              </>
-            )}
-          </div>
-
           <div className="main-buttons">
             <button 
-              className="btn btn-keep"
-              onClick={() => onKeepHand()}
+              className={`btn btn-keep ${bottomCards.length !== cardsToBottom ? 'disabled' : ''}`}
+              onClick={() => onKeepHand(bottomCards)}
+              disabled={bottomCards.length !== cardsToBottom}
             >
               <span className="btn-icon">✓</span>
               Keep This Hand
             </button>
             <button 
-              className={`btn btn-mulligan ${mulliganCount >= maxMulligans ? 'disabled' : ''}`}
+              className={`btn btn-mulligan ${!canMulligan ? 'disabled' : ''}`}
               onClick={handleMulligan}
-              disabled={mulliganCount >= maxMulligans}
+              disabled={!canMulligan}
             >
               <span className="btn-icon">↻</span>
-              Mulligan {mulliganCount > 0 ? `(${mulliganCount}/${maxMulligans})` : ''}
+              Mulligan {mulliganCount > 0 ? `(${mulliganCount})` : ''}
             </button>
           </div>
-
-          <button className="btn btn-options">
-            Options ⋮
-          </button>
         </div>
 
         {/* Info Text */}
         <div className="mulligan-info">
           <p>
-            {cards && cards.length > 0 
-              ? `Your opening hand has ${cards.length} card${cards.length !== 1 ? 's' : ''}`
-              : 'Waiting for cards...'}
+            {cardsToBottom > 0
+              ? `Pick ${cardsToBottom} card${cardsToBottom !== 1 ? 's' : ''} to put on the bottom of your library (${bottomCards.length}/${cardsToBottom})`
+              : `Your opening hand has ${cards ? cards.length : 0} card${cards && cards.length !== 1 ? 's' : ''}`}
           </p>
+          {waitingFor.length > 0 && (
+            <p>Still deciding: {waitingFor.join(', ')}</p>
+          )}
         </div>
       </div>
     </div>
@@ -2,7 +2,8 @@ import React, { useState } from 'react';
 import '../styles/ScryInterface.css';
 
 const ScryInterface = ({ libraryCards, playerName, scryCount, onComplete, onCancel }) => {
-  const cardsToView = libraryCards.slice(0, scryCount || libraryCards.length);
+  // libraryCards is in library order, so the top cards are at the end; show the top card first
+  const cardsToView = libraryCards.slice(-(scryCount || libraryCards.length)).reverse();
   const [viewingCards, setViewingCards] = useState(cardsToView);
   const [topCards, setTopCards] = useState([]);
   const [bottomCards, setBottomCards] = useState([]);
@@ -0,0 +1,112 @@
+import React, { useContext } from 'react';
+import '../styles/StackZone.css';
+import { WebSocketContext } from './GameTable';
+
+const StackZone = ({ gameState, playerId }) => {
+  const ws = useContext(WebSocketContext);
+  const stack = gameState?.stack || [];
+  const sharedCards = gameState?.battlefield || [];
+  const players = gameState?.players || {};
+
+  if (stack.length === 0 && sharedCards.length === 0) {
+    return null;
+  }
+
+  const send = (message) => {
+    if (ws && ws.readyState === WebSocket.OPEN) {
+      ws.send(JSON.stringify(message));
+    }
+  };
+
+  const getCardImagePath = (card) => {
+    if (!card.name || !card.set_code || !card.collector_number) {
+      return '/GameTableData/General/blank.jpg';
+    }
+    const face = card.is_two_sided && card.is_back_face ? '-b' : '';
+    return `/GameTableData/Sets/${card.set_code}/${card.set_code}/${card.collector_number}${face}.jpg`;
+  };
+
+  // The stack is stored bottom first; moving an item swaps it with its neighbour
+  const moveItem = (index, direction) => {
+    const target = index + direction;
+    if (target < 0 || target >= stack.length) return;
+    const order = stack.map(item => item.card.id);
+    [order[index], order[target]] = [order[target], order[index]];
+    send({ ReorderStack: { card_ids: order } });
+  };
+
+  return (
+    <div className="stack-zone">
+      {stack.length > 0 && (
+        <div className="stack-section">
+          <div className="stack-header">
+            <span>Stack ({stack.length})</span>
+            <button className="stack-btn" onClick={() => send({ ResolveStack: {} })}>
+              Resolve Top
+            </button>
+          </div>
+          <div className="stack-items">
+            {stack.map((item, index) => ({ item, index })).reverse().map(({ item, index }) => (
+              <div key={item.card.id} className="stack-item">
+                <div
+                  className="stack-card"
+                  style={{ backgroundImage: `url('${getCardImagePath(item.card)}')` }}
+                  title={item.card.name || 'Face-down spell'}
+                />
+                <div className="stack-item-info">
+                  <span className="stack-card-name">{item.card.name || 'Face-down spell'}</span>
+                  <span className="stack-card-controller">
+                    {players[item.controller_id]?.name || 'Unknown'}
+                    {item.controller_id === playerId ? ' (you)' : ''}
+                  </span>
+                </div>
+                <div className="stack-item-actions">
+                  <button className="stack-btn" onClick={() => moveItem(index, 1)} title="Move up">↑</button>
+                  <button className="stack-btn" onClick={() => moveItem(index, -1)} title="Move down">↓</button>
+                  <button
+                    className="stack-btn counter"
+                    onClick={() => send({ CounterSpell: { card_id: item.card.id } })}
+                  >
+                    Counter
+                  </button>
+                </div>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
+      {sharedCards.length > 0 && (
+        <div className="stack-section">
+          <div className="stack-header">
+            <span>Shared</span>
+          </div>
+          <div className="shared-cards">
+            {sharedCards.map(card => (
+              <div key={card.id} className="shared-card-wrapper">
+                <div
+                  className="stack-card"
+                  style={{ backgroundImage: `url('${getCardImagePath(card)}')` }}
+                  title={`${card.name} (${players[card.owner_id]?.name || 'Unknown'})`}
+                >
+                  {card.counters && Object.entries(card.counters).map(([type, count]) => (
+                    <div key={type} className="card-counter">{count} {type}</div>
+                  ))}
+                </div>
+                <button
+                  className="stack-btn"
+                  onClick={() => send({ RemoveSharedCard: { card_id: card.id } })}
+                  title="Remove from the table"
+                >
+                  ✕
+                </button>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default StackZone;
@@ -2,7 +2,8 @@ import React, { useState } from 'react';
 import '../styles/SurveilInterface.css';
 
 const SurveilInterface = ({ libraryCards, playerName, surveilCount, onComplete, onCancel }) => {
-  const cardsToView = libraryCards.slice(0, surveilCount || libraryCards.length);
+  // libraryCards is in library order, so the top cards are at the end; show the top card first
+  const cardsToView = libraryCards.slice(-(surveilCount || libraryCards.length)).reverse();
   const [viewingCards, setViewingCards] = useState(cardsToView);
   const [graveyardCards, setGraveyardCards] = useState([]);
 
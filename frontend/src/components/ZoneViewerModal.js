@@ -19,16 +19,48 @@ const ZoneViewerModal = ({ zoneName, cards, onClose, playerId = null }) => {
   const sendCardTo = (toZone) => {
     if (!contextMenu || !ws || ws.readyState !== WebSocket.OPEN || !playerId) return;
 
-    const payload = {
-      MoveCard: {
-        player_id: playerId,
-        card_id: contextMenu.card.id,
-        from_zone: zoneName.toLowerCase(),
-        to_zone: toZone === 'library_top' || toZone === 'library_bottom' || toZone === 'library_shuffle' ? 'library' : toZone
+    const fromZone = zoneName.toLowerCase();
+    if (toZone.startsWith('library')) {
+      let position = 1;
+      if (toZone === 'library_nth') {
+        position = parseInt(window.prompt('How many cards from the top? (1 is the top)', '2'), 10);
+        if (!(position > 0)) {
+          setContextMenu(null);
+          return;
+        }
       }
-    };
-
-    ws.send(JSON.stringify(payload));
+      ws.send(JSON.stringify({
+        PutInLibrary: {
+          player_id: playerId,
+          card_id: contextMenu.card.id,
+          from_zone: fromZone,
+          position,
+          from_bottom: toZone === 'library_bottom'
+        }
+      }));
+      if (toZone === 'library_shuffle') {
+        ws.send(JSON.stringify({ ShuffleLibrary: { player_id: playerId } }));
+      }
+    } else if (fromZone === 'library') {
+      // Searching shuffles the library, so the rest of this list is stale afterwards
+      ws.send(JSON.stringify({
+        TutorCard: {
+          player_id: playerId,
+          card_id: contextMenu.card.id,
+          to_zone: toZone
+        }
+      }));
+      onClose();
+    } else {
+      ws.send(JSON.stringify({
+        MoveCard: {
+          player_id: playerId,
+          card_id: contextMenu.card.id,
+          from_zone: fromZone,
+          to_zone: toZone
+        }
+      }));
+    }
     setContextMenu(null);
   };
 
@@ -100,15 +132,22 @@ const ZoneViewerModal = ({ zoneName, cards, onClose, playerId = null }) => {
                     To Exile
                   </button>
                 )}
-                <button className="submenu-item" onClick={() => sendCardTo('library_top')}>
-                  To the Top of the Library
-                </button>
-                <button className="submenu-item" onClick={() => sendCardTo('library_bottom')}>
-                  To the Bottom of the Library
-                </button>
-                <button className="submenu-item" onClick={() => sendCardTo('library_shuffle')}>
-                  Shuffled into the Library
-                </button>
+                {zoneName !== 'Library' && (
+                  <>
+                    <button className="submenu-item" onClick={() => sendCardTo('library_top')}>
+                      To the Top of the Library
+                    </button>
+                    <button className="submenu-item" onClick={() => sendCardTo('library_nth')}>
+                      Nth from the Top of the Library...
+                    </button>
+                    <button className="submenu-item" onClick={() => sendCardTo('library_bottom')}>
+                      To the Bottom of the Library
+                    </button>
+                    <button className="submenu-item" onClick={() => sendCardTo('library_shuffle')}>
+                      Shuffled into the Library
+                    </button>
+                  </>
+                )}
               </div>
             </div>
           </div>